@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: MIT
+pragma solidity >=0.8.0;
+
+interface IERC20 {
+    function transferFrom(address from, address to, uint tokens) external returns (bool success);
+}
+
+// ----------------------------------------------------------------------------
+// Transfers several ERC20 tokens from the caller to a recipient atomically.
+// The caller must approve this contract to spend the tokens.
+// ----------------------------------------------------------------------------
+contract Batch {
+    function transferBatch(address[] calldata tokens, address recipient, uint[] calldata amounts) external {
+        require(tokens.length == amounts.length, "Lengths of tokens and amounts differ");
+        for (uint i = 0; i < tokens.length; i++) {
+            require(IERC20(tokens[i]).transferFrom(msg.sender, recipient, amounts[i]), "Transfer failed");
+        }
+    }
+}
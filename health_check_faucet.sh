@@ -7,6 +7,4 @@ if [ -z "$FAUCET_URL" ]; then
   exit 1
 fi
 
-curl --location --request POST "$FAUCET_URL/request_ping" \
---header 'X-Ping-Header' \
---data-raw 'Healthcheck-ping'
+curl --fail --silent --show-error --location --request GET "$FAUCET_URL/health"
@@ -10,15 +10,23 @@ echo "$(date "+%F %X.%3N") I $(basename "$0"):${LINENO} $$ ${COMPONENT}:StartScr
 export EVM_LOADER=$(solana address -k /spl/bin/evm_loader-keypair.json)
 export $(/spl/bin/neon-cli --commitment confirmed --url $SOLANA_URL --evm_loader="$EVM_LOADER" neon-elf-params)
 
-BALANCE=$(solana balance | tr '.' '\t'| tr '[:space:]' '\t' | cut -f1)
-if [ "$BALANCE" -eq 0 ]; then
-    echo "$(date "+%F %X.%3N") W $(basename "$0"):${LINENO} $$ ${COMPONENT}:StartScript {} SOL balance is 0"
-    exit 1
-fi
+# Keypair of the `local` signer backend (mounted into the container).
+# Other backends (keystore, env, remote) have no keypair file:
+# balances of their operators are reported by the `/ready` endpoint.
+KEYPAIR=${NEON_OPERATOR_KEYFILE:-/root/.config/solana/id.json}
+if [ -f "$KEYPAIR" ]; then
+    BALANCE=$(solana balance -k "$KEYPAIR" | tr '.' '\t'| tr '[:space:]' '\t' | cut -f1)
+    if [ "$BALANCE" -eq 0 ]; then
+        echo "$(date "+%F %X.%3N") W $(basename "$0"):${LINENO} $$ ${COMPONENT}:StartScript {} SOL balance is 0"
+        exit 1
+    fi
 
-if [ "$(spl-token balance "$NEON_TOKEN_MINT" || echo 0)" -eq 0 ]; then
-    echo "$(date "+%F %X.%3N") W $(basename "$0"):${LINENO} $$ ${COMPONENT}:StartScript {} NEON balance is 0"
-    exit 1
+    if [ "$(spl-token balance --owner "$KEYPAIR" "$NEON_TOKEN_MINT" || echo 0)" -eq 0 ]; then
+        echo "$(date "+%F %X.%3N") W $(basename "$0"):${LINENO} $$ ${COMPONENT}:StartScript {} NEON balance is 0"
+        exit 1
+    fi
+else
+    echo "$(date "+%F %X.%3N") I $(basename "$0"):${LINENO} $$ ${COMPONENT}:StartScript {} Keypair $KEYPAIR is missing, skipping balance checks"
 fi
 
 faucet run
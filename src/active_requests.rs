@@ -15,6 +15,11 @@ pub fn increment() -> Guard {
     Guard {}
 }
 
+/// Returns current number of concurrent requests.
+pub fn get() -> isize {
+    COUNTER.load(Ordering::Relaxed)
+}
+
 /// Decrements counter of concurrent requests.
 fn decrement() {
     COUNTER.fetch_sub(1, Ordering::Relaxed);
@@ -0,0 +1,71 @@
+//! Faucet API keys authentication module.
+
+use sha2::{Digest as _, Sha256};
+use tracing::{info, warn};
+
+use crate::config::{self, ApiKey};
+use crate::id::ReqId;
+
+/// Represents the authentication errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("API key is required")]
+    Missing,
+
+    #[error("API key is invalid")]
+    Invalid,
+
+    #[error("API key '{0}' is not allowed to use endpoint '{1}'")]
+    Forbidden(String, String),
+}
+
+/// Represents the caller of an endpoint.
+#[derive(Debug, Clone)]
+pub enum Caller {
+    Anonymous,
+    Key(ApiKey),
+}
+
+impl Caller {
+    /// Returns the largest amount per request if the caller overrides the default.
+    pub fn max_amount(&self) -> Option<u64> {
+        match self {
+            Caller::Key(key) if key.max_amount > 0 => Some(key.max_amount),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies the caller by value of the `Authorization` header (`Bearer <key>`).
+pub fn authorize(id: &ReqId, endpoint: &str, header: Option<&str>) -> Result<Caller, Error> {
+    let key = match header {
+        Some(h) => h.strip_prefix("Bearer ").unwrap_or(h).trim(),
+        None if config::auth_anonymous() => return Ok(Caller::Anonymous),
+        None => return Err(Error::Missing),
+    };
+
+    let key = config::auth_key(&hash(key)).ok_or(Error::Invalid)?;
+    if !key.allows(endpoint) {
+        warn!(
+            "{} Key '{}' is not allowed to use {}",
+            id, key.name, endpoint
+        );
+        return Err(Error::Forbidden(key.name, endpoint.into()));
+    }
+
+    info!("{} Authorized by key '{}'", id, key.name);
+    Ok(Caller::Key(key))
+}
+
+/// Returns SHA-256 hash (hex) of the key.
+pub fn hash(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+#[test]
+fn test_hash() {
+    assert_eq!(
+        hash("secret"),
+        "2bb80d537b1da3e38bd30361aa855686bde0eacd7162fef6a25fe97bf527a25b"
+    );
+}
@@ -0,0 +1,141 @@
+//! Faucet captcha verification module (hCaptcha, reCAPTCHA, Turnstile).
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::config;
+use crate::id::ReqId;
+
+/// Timeout of a request to the verifier.
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static::lazy_static! {
+    /// HTTP client shared by all verifications (created on first use).
+    static ref CLIENT: Mutex<Option<reqwest::Client>> = Mutex::new(None);
+}
+
+/// Represents the captcha verification errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Captcha token is missing")]
+    Missing,
+
+    #[error("Captcha token is rejected {0:?}")]
+    Rejected(Vec<String>),
+
+    #[error("Captcha verifier is unavailable")]
+    Unavailable(#[from] reqwest::Error),
+}
+
+/// Represents response of the verifier (common for all supported providers).
+#[derive(Debug, Deserialize)]
+struct Verification {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// Verifies the captcha token if the endpoint requires it.
+pub async fn verify(
+    id: &ReqId,
+    endpoint: &str,
+    token: Option<&str>,
+    ip: Option<&str>,
+) -> Result<(), Error> {
+    if !config::captcha_required(endpoint) {
+        return Ok(());
+    }
+
+    let token = token.filter(|t| !t.is_empty()).ok_or(Error::Missing)?;
+    let r = request(
+        &config::captcha_verify_url(),
+        &config::captcha_secret(),
+        token,
+        ip,
+    )
+    .await;
+
+    match &r {
+        Ok(()) => info!("{} Captcha is verified", id),
+        Err(e) => warn!("{} {}", id, e),
+    }
+    r
+}
+
+/// Sends the token to the verifier.
+async fn request(url: &str, secret: &str, token: &str, ip: Option<&str>) -> Result<(), Error> {
+    let mut form = vec![("secret", secret), ("response", token)];
+    if let Some(ip) = ip {
+        form.push(("remoteip", ip));
+    }
+
+    let verification: Verification = client()?
+        .post(url)
+        .form(&form)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    if !verification.success {
+        return Err(Error::Rejected(verification.error_codes));
+    }
+    Ok(())
+}
+
+/// Returns the shared HTTP client, creating it if needed.
+fn client() -> Result<reqwest::Client, Error> {
+    let mut client = CLIENT.lock().unwrap();
+    if let Some(client) = client.as_ref() {
+        return Ok(client.clone());
+    }
+    let created = reqwest::Client::builder().timeout(VERIFY_TIMEOUT).build()?;
+    Ok(client.insert(created).clone())
+}
+
+#[test]
+fn test_request() {
+    use std::io::{Read as _, Write as _};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/siteverify", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        for body in [
+            r#"{"success": true}"#,
+            r#"{"success": false, "error-codes": ["invalid-input-response"]}"#,
+        ] {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut buf = [0; 4096];
+            // Read until the form is received completely
+            while !request.contains("response=token") {
+                let n = stream.read(&mut buf).unwrap();
+                request.push_str(&String::from_utf8_lossy(&buf[..n]));
+            }
+            assert!(request.starts_with("POST /siteverify"));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        assert!(request(&url, "secret", "token", Some("127.0.0.1"))
+            .await
+            .is_ok());
+        let r = request(&url, "secret", "token", None).await;
+        assert!(matches!(r, Err(Error::Rejected(codes)) if codes == ["invalid-input-response"]));
+    });
+    server.join().unwrap();
+}
@@ -3,13 +3,14 @@
 use std::collections::HashMap;
 use std::convert::TryFrom as _;
 use std::env;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::str::FromStr as _;
 use std::sync::RwLock;
 
 use serde::{Deserialize, Serialize};
 
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::account_utils::StateMut;
@@ -43,6 +44,9 @@ pub enum Error {
     #[error("Failed to parse integer number from config")]
     ParseInt(#[from] std::num::ParseIntError),
 
+    #[error("Failed to parse floating point number from config")]
+    ParseFloat(#[from] std::num::ParseFloatError),
+
     #[error("Failed to parse string literal '{0}' from config")]
     ParseString(String),
 
@@ -72,6 +76,15 @@ pub enum Error {
 
     #[error("Account is not BPF '{0}'")]
     AccountIsNotBpf(Pubkey),
+
+    #[error("Failed to parse ELF of EVM Loader: {0}")]
+    ParseElf(#[from] goblin::error::Error),
+
+    #[error("NEON parameters not found in EVM Loader")]
+    NeonParamsNotFound,
+
+    #[error("Solana does not respond: {0}")]
+    Join(#[from] tokio::task::JoinError),
 }
 
 /// Represents the config result type.
@@ -81,6 +94,8 @@ const FAUCET_REVISION: &str = "FAUCET_REVISION";
 const FAUCET_RPC_BIND: &str = "FAUCET_RPC_BIND";
 const FAUCET_RPC_PORT: &str = "FAUCET_RPC_PORT";
 const FAUCET_RPC_ALLOWED_ORIGINS: &str = "FAUCET_RPC_ALLOWED_ORIGINS";
+const FAUCET_RPC_ASYNC_AIRDROP: &str = "FAUCET_RPC_ASYNC_AIRDROP";
+const FAUCET_RPC_WATCH_CONFIG: &str = "FAUCET_RPC_WATCH_CONFIG";
 const FAUCET_WEB3_ENABLE: &str = "FAUCET_WEB3_ENABLE";
 const WEB3_RPC_URL: &str = "WEB3_RPC_URL";
 const WEB3_PRIVATE_KEY: &str = "WEB3_PRIVATE_KEY";
@@ -98,6 +113,57 @@ const NEON_HEAP_FRAME: &str = "NEON_HEAP_FRAME";
 const NEON_ADDITIONAL_FEE: &str = "NEON_ADDITIONAL_FEE";
 const NEON_OPERATOR_KEYFILE: &str = "NEON_OPERATOR_KEYFILE";
 const NEON_ETH_MAX_AMOUNT: &str = "NEON_ETH_MAX_AMOUNT";
+const FAUCET_SOLANA_SOL_MAX_AMOUNT: &str = "FAUCET_SOLANA_SOL_MAX_AMOUNT";
+const FAUCET_SOLANA_SPL_MINTS: &str = "FAUCET_SOLANA_SPL_MINTS";
+const FAUCET_SOLANA_SPL_MAX_AMOUNT: &str = "FAUCET_SOLANA_SPL_MAX_AMOUNT";
+const FAUCET_SOLANA_NEON_PARAMS_REFRESH: &str = "FAUCET_SOLANA_NEON_PARAMS_REFRESH";
+const FAUCET_SOLANA_PRIORITY_FEE: &str = "FAUCET_SOLANA_PRIORITY_FEE";
+const FAUCET_SOLANA_EVM_LOADER_ELF: &str = "FAUCET_SOLANA_EVM_LOADER_ELF";
+const FAUCET_WEB3_SIGNER_BACKEND: &str = "FAUCET_WEB3_SIGNER_BACKEND";
+const FAUCET_WEB3_SIGNER_KEYSTORE: &str = "FAUCET_WEB3_SIGNER_KEYSTORE";
+const FAUCET_WEB3_SIGNER_URL: &str = "FAUCET_WEB3_SIGNER_URL";
+const FAUCET_WEB3_SIGNER_PUBLIC: &str = "FAUCET_WEB3_SIGNER_PUBLIC";
+const FAUCET_SOLANA_SIGNER_BACKEND: &str = "FAUCET_SOLANA_SIGNER_BACKEND";
+const FAUCET_SOLANA_SIGNER_KEYSTORE: &str = "FAUCET_SOLANA_SIGNER_KEYSTORE";
+const FAUCET_SOLANA_SIGNER_URL: &str = "FAUCET_SOLANA_SIGNER_URL";
+const FAUCET_SOLANA_SIGNER_PUBLIC: &str = "FAUCET_SOLANA_SIGNER_PUBLIC";
+const FAUCET_WEB3_PRIVATE_KEYS: &str = "FAUCET_WEB3_PRIVATE_KEYS";
+const FAUCET_WEB3_OPERATOR_SELECTION: &str = "FAUCET_WEB3_OPERATOR_SELECTION";
+const FAUCET_WEB3_OPERATOR_MIN_BALANCE: &str = "FAUCET_WEB3_OPERATOR_MIN_BALANCE";
+const FAUCET_WEB3_REPLACE_TIMEOUT: &str = "FAUCET_WEB3_REPLACE_TIMEOUT";
+const FAUCET_WEB3_CONFIRMATIONS: &str = "FAUCET_WEB3_CONFIRMATIONS";
+const FAUCET_WEB3_CONFIRMATION_TIMEOUT: &str = "FAUCET_WEB3_CONFIRMATION_TIMEOUT";
+const FAUCET_WEB3_GAS_MULTIPLIER: &str = "FAUCET_WEB3_GAS_MULTIPLIER";
+const FAUCET_WEB3_MAX_GAS_PRICE: &str = "FAUCET_WEB3_MAX_GAS_PRICE";
+const FAUCET_WEB3_MAX_FEE_PER_GAS: &str = "FAUCET_WEB3_MAX_FEE_PER_GAS";
+const FAUCET_WEB3_MAX_PRIORITY_FEE_PER_GAS: &str = "FAUCET_WEB3_MAX_PRIORITY_FEE_PER_GAS";
+const FAUCET_WEB3_BATCH_CONTRACT: &str = "FAUCET_WEB3_BATCH_CONTRACT";
+const FAUCET_WEB3_MINT_TOKENS: &str = "FAUCET_WEB3_MINT_TOKENS";
+const FAUCET_WEB3_MINT_FUNCTION: &str = "FAUCET_WEB3_MINT_FUNCTION";
+const FAUCET_WEB3_MINT_ABI: &str = "FAUCET_WEB3_MINT_ABI";
+const FAUCET_SOLANA_OPERATOR_KEYFILES: &str = "FAUCET_SOLANA_OPERATOR_KEYFILES";
+const FAUCET_SOLANA_OPERATOR_SELECTION: &str = "FAUCET_SOLANA_OPERATOR_SELECTION";
+const FAUCET_SOLANA_OPERATOR_MIN_BALANCE: &str = "FAUCET_SOLANA_OPERATOR_MIN_BALANCE";
+const FAUCET_LIMITS_ENABLE: &str = "FAUCET_LIMITS_ENABLE";
+const FAUCET_LIMITS_WINDOW: &str = "FAUCET_LIMITS_WINDOW";
+const FAUCET_LIMITS_WALLET_REQUESTS: &str = "FAUCET_LIMITS_WALLET_REQUESTS";
+const FAUCET_LIMITS_WALLET_NEON: &str = "FAUCET_LIMITS_WALLET_NEON";
+const FAUCET_LIMITS_WALLET_ERC20: &str = "FAUCET_LIMITS_WALLET_ERC20";
+const FAUCET_LIMITS_WALLET_SOL: &str = "FAUCET_LIMITS_WALLET_SOL";
+const FAUCET_LIMITS_WALLET_SPL: &str = "FAUCET_LIMITS_WALLET_SPL";
+const FAUCET_LIMITS_IP_REQUESTS: &str = "FAUCET_LIMITS_IP_REQUESTS";
+const FAUCET_LIMITS_IP_NEON: &str = "FAUCET_LIMITS_IP_NEON";
+const FAUCET_LIMITS_IP_ERC20: &str = "FAUCET_LIMITS_IP_ERC20";
+const FAUCET_LIMITS_IP_SOL: &str = "FAUCET_LIMITS_IP_SOL";
+const FAUCET_LIMITS_IP_SPL: &str = "FAUCET_LIMITS_IP_SPL";
+const FAUCET_LIMITS_TRUSTED_PROXIES: &str = "FAUCET_LIMITS_TRUSTED_PROXIES";
+const FAUCET_STORAGE_ENABLE: &str = "FAUCET_STORAGE_ENABLE";
+const FAUCET_STORAGE_PATH: &str = "FAUCET_STORAGE_PATH";
+const FAUCET_CAPTCHA_ENABLE: &str = "FAUCET_CAPTCHA_ENABLE";
+const FAUCET_CAPTCHA_VERIFY_URL: &str = "FAUCET_CAPTCHA_VERIFY_URL";
+const FAUCET_CAPTCHA_SECRET: &str = "FAUCET_CAPTCHA_SECRET";
+const FAUCET_CAPTCHA_ENDPOINTS: &str = "FAUCET_CAPTCHA_ENDPOINTS";
+const FAUCET_AUTH_ANONYMOUS: &str = "FAUCET_AUTH_ANONYMOUS";
 const NEON_LOG: &str = "NEON_LOG";
 const RUST_LOG: &str = "RUST_LOG";
 
@@ -106,6 +172,8 @@ static ENV: &[&str] = &[
     FAUCET_RPC_BIND,
     FAUCET_RPC_PORT,
     FAUCET_RPC_ALLOWED_ORIGINS,
+    FAUCET_RPC_ASYNC_AIRDROP,
+    FAUCET_RPC_WATCH_CONFIG,
     FAUCET_WEB3_ENABLE,
     WEB3_RPC_URL,
     WEB3_PRIVATE_KEY,
@@ -117,6 +185,57 @@ static ENV: &[&str] = &[
     EVM_LOADER,
     NEON_OPERATOR_KEYFILE,
     NEON_ETH_MAX_AMOUNT,
+    FAUCET_SOLANA_SOL_MAX_AMOUNT,
+    FAUCET_SOLANA_SPL_MINTS,
+    FAUCET_SOLANA_SPL_MAX_AMOUNT,
+    FAUCET_SOLANA_NEON_PARAMS_REFRESH,
+    FAUCET_SOLANA_PRIORITY_FEE,
+    FAUCET_SOLANA_EVM_LOADER_ELF,
+    FAUCET_WEB3_SIGNER_BACKEND,
+    FAUCET_WEB3_SIGNER_KEYSTORE,
+    FAUCET_WEB3_SIGNER_URL,
+    FAUCET_WEB3_SIGNER_PUBLIC,
+    FAUCET_SOLANA_SIGNER_BACKEND,
+    FAUCET_SOLANA_SIGNER_KEYSTORE,
+    FAUCET_SOLANA_SIGNER_URL,
+    FAUCET_SOLANA_SIGNER_PUBLIC,
+    FAUCET_WEB3_PRIVATE_KEYS,
+    FAUCET_WEB3_OPERATOR_SELECTION,
+    FAUCET_WEB3_OPERATOR_MIN_BALANCE,
+    FAUCET_WEB3_REPLACE_TIMEOUT,
+    FAUCET_WEB3_CONFIRMATIONS,
+    FAUCET_WEB3_CONFIRMATION_TIMEOUT,
+    FAUCET_WEB3_GAS_MULTIPLIER,
+    FAUCET_WEB3_MAX_GAS_PRICE,
+    FAUCET_WEB3_MAX_FEE_PER_GAS,
+    FAUCET_WEB3_MAX_PRIORITY_FEE_PER_GAS,
+    FAUCET_WEB3_BATCH_CONTRACT,
+    FAUCET_WEB3_MINT_TOKENS,
+    FAUCET_WEB3_MINT_FUNCTION,
+    FAUCET_WEB3_MINT_ABI,
+    FAUCET_SOLANA_OPERATOR_KEYFILES,
+    FAUCET_SOLANA_OPERATOR_SELECTION,
+    FAUCET_SOLANA_OPERATOR_MIN_BALANCE,
+    FAUCET_LIMITS_ENABLE,
+    FAUCET_LIMITS_WINDOW,
+    FAUCET_LIMITS_WALLET_REQUESTS,
+    FAUCET_LIMITS_WALLET_NEON,
+    FAUCET_LIMITS_WALLET_ERC20,
+    FAUCET_LIMITS_WALLET_SOL,
+    FAUCET_LIMITS_WALLET_SPL,
+    FAUCET_LIMITS_IP_REQUESTS,
+    FAUCET_LIMITS_IP_NEON,
+    FAUCET_LIMITS_IP_ERC20,
+    FAUCET_LIMITS_IP_SOL,
+    FAUCET_LIMITS_IP_SPL,
+    FAUCET_LIMITS_TRUSTED_PROXIES,
+    FAUCET_STORAGE_ENABLE,
+    FAUCET_STORAGE_PATH,
+    FAUCET_CAPTCHA_ENABLE,
+    FAUCET_CAPTCHA_VERIFY_URL,
+    FAUCET_CAPTCHA_SECRET,
+    FAUCET_CAPTCHA_ENDPOINTS,
+    FAUCET_AUTH_ANONYMOUS,
     NEON_LOG,
     RUST_LOG,
 ];
@@ -142,40 +261,156 @@ pub fn show_env() {
 
 /// Loads the config from a file and applies defined environment variables.
 pub fn load(file: &Path) -> Result<()> {
+    let faucet = read(file)?;
+    *CONFIG.write().unwrap() = faucet;
+    Ok(())
+}
+
+/// Reloads the config from a file and environment variables and swaps it in.
+/// Keeps the current config if the new one is invalid.
+/// NEON parameters loaded from the EVM Loader are carried over.
+pub fn reload(file: &Path) -> Result<()> {
+    let mut faucet = read(file)?;
+
+    let mut config = CONFIG.write().unwrap();
+    if faucet.solana.evm_loader == config.solana.evm_loader
+        && faucet.solana.evm_loader_elf == config.solana.evm_loader_elf
+        && faucet.solana.neon_params == config.solana.neon_params
+    {
+        faucet.solana.keep_neon_params(&config.solana);
+    }
+
+    let old = config.to_string();
+    let new = faucet.to_string();
+    let changes = new
+        .lines()
+        .filter(|line| !old.lines().any(|l| l == *line))
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        info!("{} Config is not changed", id::default());
+    }
+    for line in changes {
+        info!("{} Config changed: {}", id::default(), line);
+    }
+
+    if faucet.rpc.bind != config.rpc.bind || faucet.rpc.port != config.rpc.port {
+        warn!(
+            "{} Changes of rpc.bind and rpc.port require restart",
+            id::default()
+        );
+    }
+    if faucet.storage.enable != config.storage.enable || faucet.storage.path != config.storage.path
+    {
+        warn!("{} Changes of storage require restart", id::default());
+    }
+
+    *config = faucet;
+    Ok(())
+}
+
+/// Reads the config from a file, applies defined environment variables and checks it.
+fn read(file: &Path) -> Result<Faucet> {
+    let mut faucet = Faucet::default();
     if file.exists() {
-        CONFIG.write().unwrap().load(file)?;
+        faucet.load(file)?;
     }
 
     for e in ENV {
         if let Ok(val) = env::var(e) {
             match *e {
                 FAUCET_REVISION => {}
-                FAUCET_RPC_BIND => CONFIG.write().unwrap().rpc.bind = val,
-                FAUCET_RPC_PORT => CONFIG.write().unwrap().rpc.port = val.parse::<u16>()?,
+                FAUCET_RPC_BIND => faucet.rpc.bind = val,
+                FAUCET_RPC_PORT => faucet.rpc.port = val.parse::<u16>()?,
                 FAUCET_RPC_ALLOWED_ORIGINS => {
-                    CONFIG.write().unwrap().rpc.allowed_origins = parse_list_of_strings(&val)?
+                    faucet.rpc.allowed_origins = parse_list_of_strings(&val)?
+                }
+                FAUCET_RPC_ASYNC_AIRDROP => faucet.rpc.async_airdrop = val.parse::<bool>()?,
+                FAUCET_RPC_WATCH_CONFIG => faucet.rpc.watch_config = val.parse::<bool>()?,
+                FAUCET_WEB3_ENABLE => faucet.web3.enable = val.parse::<bool>()?,
+                WEB3_RPC_URL => faucet.web3.rpc_url = val,
+                WEB3_PRIVATE_KEY => faucet.web3.private_key = val,
+                NEON_ERC20_TOKENS => faucet.web3.tokens = parse_list_of_strings(&val)?,
+                NEON_ERC20_MAX_AMOUNT => faucet.web3.max_amount = val.parse::<u64>()?,
+                FAUCET_SOLANA_ENABLE => faucet.solana.enable = val.parse::<bool>()?,
+                SOLANA_URL => faucet.solana.url = val,
+                SOLANA_COMMITMENT => faucet.solana.commitment = val,
+                EVM_LOADER => faucet.solana.evm_loader = val,
+                NEON_OPERATOR_KEYFILE => faucet.solana.operator_keyfile = val.into(),
+                NEON_ETH_MAX_AMOUNT => faucet.solana.max_amount = val.parse::<u64>()?,
+                FAUCET_SOLANA_SOL_MAX_AMOUNT => {
+                    faucet.solana.sol_max_amount = val.parse::<u64>()?
+                }
+                FAUCET_SOLANA_SPL_MINTS => faucet.solana.spl_mints = parse_list_of_strings(&val)?,
+                FAUCET_SOLANA_SPL_MAX_AMOUNT => {
+                    faucet.solana.spl_max_amount = val.parse::<u64>()?
+                }
+                FAUCET_SOLANA_NEON_PARAMS_REFRESH => {
+                    faucet.solana.neon_params_refresh = val.parse::<u64>()?
+                }
+                FAUCET_SOLANA_PRIORITY_FEE => faucet.solana.priority_fee = val.parse::<u32>()?,
+                FAUCET_SOLANA_EVM_LOADER_ELF => faucet.solana.evm_loader_elf = val.into(),
+                FAUCET_WEB3_SIGNER_BACKEND => faucet.web3.signer.backend = val,
+                FAUCET_WEB3_SIGNER_KEYSTORE => faucet.web3.signer.keystore = val.into(),
+                FAUCET_WEB3_SIGNER_URL => faucet.web3.signer.url = val,
+                FAUCET_WEB3_SIGNER_PUBLIC => faucet.web3.signer.public = val,
+                FAUCET_SOLANA_SIGNER_BACKEND => faucet.solana.signer.backend = val,
+                FAUCET_SOLANA_SIGNER_KEYSTORE => faucet.solana.signer.keystore = val.into(),
+                FAUCET_SOLANA_SIGNER_URL => faucet.solana.signer.url = val,
+                FAUCET_SOLANA_SIGNER_PUBLIC => faucet.solana.signer.public = val,
+                FAUCET_WEB3_PRIVATE_KEYS => faucet.web3.private_keys = parse_list_of_strings(&val)?,
+                FAUCET_WEB3_OPERATOR_SELECTION => faucet.web3.operator_selection = val,
+                FAUCET_WEB3_OPERATOR_MIN_BALANCE => {
+                    faucet.web3.operator_min_balance = val.parse::<f64>()?
                 }
-                FAUCET_WEB3_ENABLE => CONFIG.write().unwrap().web3.enable = val.parse::<bool>()?,
-                WEB3_RPC_URL => CONFIG.write().unwrap().web3.rpc_url = val,
-                WEB3_PRIVATE_KEY => CONFIG.write().unwrap().web3.private_key = val,
-                NEON_ERC20_TOKENS => {
-                    CONFIG.write().unwrap().web3.tokens = parse_list_of_strings(&val)?
+                FAUCET_WEB3_REPLACE_TIMEOUT => faucet.web3.replace_timeout = val.parse::<u64>()?,
+                FAUCET_WEB3_CONFIRMATIONS => faucet.web3.confirmations = val.parse::<u64>()?,
+                FAUCET_WEB3_CONFIRMATION_TIMEOUT => {
+                    faucet.web3.confirmation_timeout = val.parse::<u64>()?
                 }
-                NEON_ERC20_MAX_AMOUNT => {
-                    CONFIG.write().unwrap().web3.max_amount = val.parse::<u64>()?
+                FAUCET_WEB3_GAS_MULTIPLIER => faucet.web3.gas_multiplier = val.parse::<f64>()?,
+                FAUCET_WEB3_MAX_GAS_PRICE => faucet.web3.max_gas_price = val.parse::<u64>()?,
+                FAUCET_WEB3_MAX_FEE_PER_GAS => faucet.web3.max_fee_per_gas = val.parse::<u64>()?,
+                FAUCET_WEB3_MAX_PRIORITY_FEE_PER_GAS => {
+                    faucet.web3.max_priority_fee_per_gas = val.parse::<u64>()?
                 }
-                FAUCET_SOLANA_ENABLE => {
-                    CONFIG.write().unwrap().solana.enable = val.parse::<bool>()?
+                FAUCET_WEB3_BATCH_CONTRACT => faucet.web3.batch_contract = val,
+                FAUCET_WEB3_MINT_TOKENS => faucet.web3.mint_tokens = parse_list_of_strings(&val)?,
+                FAUCET_WEB3_MINT_FUNCTION => faucet.web3.mint_function = val,
+                FAUCET_WEB3_MINT_ABI => faucet.web3.mint_abi = val.into(),
+                FAUCET_SOLANA_OPERATOR_KEYFILES => {
+                    faucet.solana.operator_keyfiles = parse_list_of_strings(&val)?
+                        .into_iter()
+                        .map(PathBuf::from)
+                        .collect()
                 }
-                SOLANA_URL => CONFIG.write().unwrap().solana.url = val,
-                SOLANA_COMMITMENT => CONFIG.write().unwrap().solana.commitment = val,
-                EVM_LOADER => CONFIG.write().unwrap().solana.evm_loader = val,
-                NEON_OPERATOR_KEYFILE => {
-                    CONFIG.write().unwrap().solana.operator_keyfile = val.into()
+                FAUCET_SOLANA_OPERATOR_SELECTION => faucet.solana.operator_selection = val,
+                FAUCET_SOLANA_OPERATOR_MIN_BALANCE => {
+                    faucet.solana.operator_min_balance = val.parse::<f64>()?
                 }
-                NEON_ETH_MAX_AMOUNT => {
-                    CONFIG.write().unwrap().solana.max_amount = val.parse::<u64>()?
+                FAUCET_LIMITS_ENABLE => faucet.limits.enable = val.parse::<bool>()?,
+                FAUCET_LIMITS_WINDOW => faucet.limits.window = val.parse::<u64>()?,
+                FAUCET_LIMITS_WALLET_REQUESTS => {
+                    faucet.limits.wallet.requests = val.parse::<u64>()?
                 }
+                FAUCET_LIMITS_WALLET_NEON => faucet.limits.wallet.neon = val.parse::<u64>()?,
+                FAUCET_LIMITS_WALLET_ERC20 => faucet.limits.wallet.erc20 = val.parse::<u64>()?,
+                FAUCET_LIMITS_WALLET_SOL => faucet.limits.wallet.sol = val.parse::<u64>()?,
+                FAUCET_LIMITS_WALLET_SPL => faucet.limits.wallet.spl = val.parse::<u64>()?,
+                FAUCET_LIMITS_IP_REQUESTS => faucet.limits.ip.requests = val.parse::<u64>()?,
+                FAUCET_LIMITS_IP_NEON => faucet.limits.ip.neon = val.parse::<u64>()?,
+                FAUCET_LIMITS_IP_ERC20 => faucet.limits.ip.erc20 = val.parse::<u64>()?,
+                FAUCET_LIMITS_IP_SOL => faucet.limits.ip.sol = val.parse::<u64>()?,
+                FAUCET_LIMITS_IP_SPL => faucet.limits.ip.spl = val.parse::<u64>()?,
+                FAUCET_LIMITS_TRUSTED_PROXIES => {
+                    faucet.limits.trusted_proxies = parse_list_of_strings(&val)?
+                }
+                FAUCET_STORAGE_ENABLE => faucet.storage.enable = val.parse::<bool>()?,
+                FAUCET_STORAGE_PATH => faucet.storage.path = val.into(),
+                FAUCET_CAPTCHA_ENABLE => faucet.captcha.enable = val.parse::<bool>()?,
+                FAUCET_CAPTCHA_VERIFY_URL => faucet.captcha.verify_url = val,
+                FAUCET_CAPTCHA_SECRET => faucet.captcha.secret = val,
+                FAUCET_CAPTCHA_ENDPOINTS => faucet.captcha.endpoints = parse_list_of_strings(&val)?,
+                FAUCET_AUTH_ANONYMOUS => faucet.auth.anonymous = val.parse::<bool>()?,
                 NEON_LOG => {}
                 RUST_LOG => {}
                 _ => unreachable!(),
@@ -183,9 +418,10 @@ pub fn load(file: &Path) -> Result<()> {
         }
     }
 
-    CONFIG.read().unwrap().check()?;
+    faucet.check()?;
+    faucet.web3.load_mint_abi()?;
 
-    Ok(())
+    Ok(faucet)
 }
 
 /// Shows the current config.
@@ -213,6 +449,16 @@ pub fn allowed_origins() -> Vec<String> {
     CONFIG.read().unwrap().rpc.allowed_origins.clone()
 }
 
+/// Gets the `rpc.async_airdrop` value.
+pub fn rpc_async_airdrop() -> bool {
+    CONFIG.read().unwrap().rpc.async_airdrop
+}
+
+/// Gets the `rpc.watch_config` value.
+pub fn rpc_watch_config() -> bool {
+    CONFIG.read().unwrap().rpc.watch_config
+}
+
 /// Gets the `web3.enable` value.
 pub fn web3_enabled() -> bool {
     CONFIG.read().unwrap().web3.enable
@@ -229,14 +475,105 @@ pub fn web3_private_key() -> String {
     ethereum::strip_0x_prefix(key).to_owned()
 }
 
-/// Gets the `web3.tokens` addresses.
+/// Gets the `web3.signer` value.
+pub fn web3_signer() -> Signer {
+    CONFIG.read().unwrap().web3.signer.clone()
+}
+
+/// Gets the `web3.private_keys` values. Removes prefix 0x if any.
+pub fn web3_private_keys() -> Vec<String> {
+    CONFIG
+        .read()
+        .unwrap()
+        .web3
+        .private_keys
+        .iter()
+        .map(|key| ethereum::strip_0x_prefix(key).to_owned())
+        .collect()
+}
+
+/// Gets the `web3.operator_selection` value.
+pub fn web3_operator_selection() -> String {
+    CONFIG.read().unwrap().web3.operator_selection.clone()
+}
+
+/// Gets the `web3.operator_min_balance` value.
+pub fn web3_operator_min_balance() -> f64 {
+    CONFIG.read().unwrap().web3.operator_min_balance
+}
+
+/// Gets the `web3.replace_timeout` value.
+pub fn web3_replace_timeout() -> u64 {
+    CONFIG.read().unwrap().web3.replace_timeout
+}
+
+/// Gets the `web3.confirmations` value.
+pub fn web3_confirmations() -> u64 {
+    CONFIG.read().unwrap().web3.confirmations
+}
+
+/// Gets the `web3.confirmation_timeout` value.
+pub fn web3_confirmation_timeout() -> u64 {
+    CONFIG.read().unwrap().web3.confirmation_timeout
+}
+
+/// Gets the `web3.gas_multiplier` value.
+pub fn web3_gas_multiplier() -> f64 {
+    CONFIG.read().unwrap().web3.gas_multiplier
+}
+
+/// Gets the `web3.max_gas_price` value.
+pub fn web3_max_gas_price() -> u64 {
+    CONFIG.read().unwrap().web3.max_gas_price
+}
+
+/// Gets the `web3.max_fee_per_gas` value.
+pub fn web3_max_fee_per_gas() -> u64 {
+    CONFIG.read().unwrap().web3.max_fee_per_gas
+}
+
+/// Gets the `web3.max_priority_fee_per_gas` value.
+pub fn web3_max_priority_fee_per_gas() -> u64 {
+    CONFIG.read().unwrap().web3.max_priority_fee_per_gas
+}
+
+/// Gets the `web3.batch_contract` value.
+pub fn web3_batch_contract() -> String {
+    CONFIG.read().unwrap().web3.batch_contract.clone()
+}
+
+/// Checks if the token is minted (listed in `web3.mint_tokens`).
+pub fn web3_is_mint_token(token: &str) -> bool {
+    CONFIG
+        .read()
+        .unwrap()
+        .web3
+        .mint_tokens
+        .iter()
+        .any(|t| same_address(t, token))
+}
+
+/// Gets the mint function parsed from `web3.mint_abi` (if any token is minted).
+pub fn web3_mint_abi_function() -> Option<web3::ethabi::Function> {
+    CONFIG.read().unwrap().web3.mint_abi_function.clone()
+}
+
+/// Gets addresses of enabled tokens of `web3.tokens` and `[[web3.token]]`.
 pub fn tokens() -> Vec<String> {
-    CONFIG.read().unwrap().web3.tokens.clone()
+    web3_tokens().into_iter().map(|t| t.address).collect()
+}
+
+/// Gets enabled tokens of `web3.tokens` and `[[web3.token]]`
+/// with `max_amount` defaulted to `web3.max_amount`.
+pub fn web3_tokens() -> Vec<Erc20Token> {
+    CONFIG.read().unwrap().web3.all_tokens()
 }
 
-/// Gets the `web3.max_amount` value.
-pub fn web3_max_amount() -> u64 {
-    CONFIG.read().unwrap().web3.max_amount
+/// Finds an enabled token by address or alias (case-insensitive).
+pub fn web3_token(name: &str) -> Option<Erc20Token> {
+    web3_tokens()
+        .into_iter()
+        .find(|t| t.address.eq_ignore_ascii_case(name) || t.alias.eq_ignore_ascii_case(name))
 }
 
 /// Gets the `solana.enable` value.
@@ -285,9 +622,50 @@ pub fn solana_token_mint_decimals() -> u8 {
     CONFIG.read().unwrap().solana.token_mint_decimals
 }
 
+/// Gets the `solana.compute_budget_units` value.
+pub fn solana_compute_budget_units() -> u32 {
+    CONFIG.read().unwrap().solana.compute_budget_units
+}
+
+/// Gets the `solana.compute_budget_heap_frame` value.
+pub fn solana_compute_budget_heap_frame() -> u32 {
+    CONFIG.read().unwrap().solana.compute_budget_heap_frame
+}
+
+/// Gets the `solana.compute_budget_additional_fee` value.
+pub fn solana_compute_budget_additional_fee() -> u32 {
+    CONFIG.read().unwrap().solana.compute_budget_additional_fee
+}
+
+/// Gets the `solana.priority_fee` value (in lamports).
+pub fn solana_priority_fee() -> u32 {
+    CONFIG.read().unwrap().solana.priority_fee
+}
+
 /// Gets the `solana.operator` keypair value.
 pub fn solana_operator_keypair() -> Result<Keypair> {
     let keyfile = CONFIG.read().unwrap().solana.operator_keyfile.clone();
+    read_keypair(keyfile)
+}
+
+/// Gets keypairs of the `solana.operator_keyfiles` values.
+pub fn solana_operator_keypairs() -> Result<Vec<Keypair>> {
+    let keyfiles = CONFIG.read().unwrap().solana.operator_keyfiles.clone();
+    keyfiles.into_iter().map(read_keypair).collect()
+}
+
+/// Gets the `solana.operator_selection` value.
+pub fn solana_operator_selection() -> String {
+    CONFIG.read().unwrap().solana.operator_selection.clone()
+}
+
+/// Gets the `solana.operator_min_balance` value.
+pub fn solana_operator_min_balance() -> f64 {
+    CONFIG.read().unwrap().solana.operator_min_balance
+}
+
+/// Reads keypair from a file with JSON array of bytes.
+fn read_keypair(keyfile: PathBuf) -> Result<Keypair> {
     let key = std::fs::read_to_string(&keyfile).map_err(|e| Error::Read(e, keyfile.clone()))?;
     let key = key.trim();
     if !(key.starts_with('[') && key.ends_with(']')) {
@@ -301,11 +679,108 @@ pub fn solana_operator_keypair() -> Result<Keypair> {
     Ok(Keypair::from_bytes(&bytes)?)
 }
 
+/// Gets the `solana.signer` value.
+pub fn solana_signer() -> Signer {
+    CONFIG.read().unwrap().solana.signer.clone()
+}
+
 /// Gets the `solana.max_amount` value
 pub fn solana_max_amount() -> u64 {
     CONFIG.read().unwrap().solana.max_amount
 }
 
+/// Gets the `solana.sol_max_amount` value.
+pub fn solana_sol_max_amount() -> u64 {
+    CONFIG.read().unwrap().solana.sol_max_amount
+}
+
+/// Gets the `solana.spl_mints` addresses.
+pub fn solana_spl_mints() -> Vec<String> {
+    CONFIG.read().unwrap().solana.spl_mints.clone()
+}
+
+/// Gets the `solana.spl_max_amount` value.
+pub fn solana_spl_max_amount() -> u64 {
+    CONFIG.read().unwrap().solana.spl_max_amount
+}
+
+/// Gets the `solana.neon_params_refresh` value (in seconds).
+pub fn solana_neon_params_refresh() -> u64 {
+    CONFIG.read().unwrap().solana.neon_params_refresh
+}
+
+/// Gets the `limits.enable` value.
+pub fn limits_enabled() -> bool {
+    CONFIG.read().unwrap().limits.enable
+}
+
+/// Gets the `limits.window` value (in seconds).
+pub fn limits_window() -> u64 {
+    CONFIG.read().unwrap().limits.window
+}
+
+/// Gets the `limits.wallet` quota.
+pub fn limits_wallet() -> Quota {
+    CONFIG.read().unwrap().limits.wallet.clone()
+}
+
+/// Gets the `limits.ip` quota.
+pub fn limits_ip() -> Quota {
+    CONFIG.read().unwrap().limits.ip.clone()
+}
+
+/// Gets the `limits.trusted_proxies` addresses.
+pub fn limits_trusted_proxies() -> Vec<IpAddr> {
+    CONFIG
+        .read()
+        .unwrap()
+        .limits
+        .trusted_proxies
+        .iter()
+        .filter_map(|ip| ip.parse().ok())
+        .collect()
+}
+
+/// Gets the `storage.enable` value.
+pub fn storage_enabled() -> bool {
+    CONFIG.read().unwrap().storage.enable
+}
+
+/// Gets the `storage.path` value.
+pub fn storage_path() -> PathBuf {
+    CONFIG.read().unwrap().storage.path.clone()
+}
+
+/// Checks if the endpoint requires captcha verification.
+pub fn captcha_required(endpoint: &str) -> bool {
+    let captcha = &CONFIG.read().unwrap().captcha;
+    captcha.enable && captcha.endpoints.iter().any(|e| e == endpoint)
+}
+
+/// Gets the `captcha.verify_url` value.
+pub fn captcha_verify_url() -> String {
+    CONFIG.read().unwrap().captcha.verify_url.clone()
+}
+
+/// Gets the `captcha.secret` value.
+pub fn captcha_secret() -> String {
+    CONFIG.read().unwrap().captcha.secret.clone()
+}
+
+/// Gets the `auth.anonymous` value.
+pub fn auth_anonymous() -> bool {
+    CONFIG.read().unwrap().auth.anonymous
+}
+
+/// Finds the API key by SHA-256 hash (hex) of its value.
+pub fn auth_key(hash: &str) -> Option<ApiKey> {
+    let auth = &CONFIG.read().unwrap().auth;
+    auth.keys
+        .iter()
+        .find(|k| k.hash.eq_ignore_ascii_case(hash))
+        .cloned()
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(default)]
 #[serde(deny_unknown_fields)]
@@ -313,6 +788,8 @@ struct Rpc {
     bind: String,
     port: u16,
     allowed_origins: Vec<String>,
+    async_airdrop: bool,
+    watch_config: bool,
 }
 
 impl Rpc {
@@ -343,14 +820,26 @@ impl std::fmt::Display for Rpc {
         }
         write!(f, "rpc.allowed_origins = {:?}", self.allowed_origins)?;
         if env::var(FAUCET_RPC_ALLOWED_ORIGINS).is_ok() {
-            write!(f, " (overridden by {})", FAUCET_RPC_ALLOWED_ORIGINS)
+            writeln!(f, " (overridden by {})", FAUCET_RPC_ALLOWED_ORIGINS)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "rpc.async_airdrop = {}", self.async_airdrop)?;
+        if env::var(FAUCET_RPC_ASYNC_AIRDROP).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_RPC_ASYNC_AIRDROP)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "rpc.watch_config = {}", self.watch_config)?;
+        if env::var(FAUCET_RPC_WATCH_CONFIG).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_RPC_WATCH_CONFIG)
         } else {
             write!(f, "")
         }
     }
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 #[serde(deny_unknown_fields)]
 struct Web3 {
@@ -359,9 +848,132 @@ struct Web3 {
     private_key: String,
     tokens: Vec<String>,
     max_amount: u64,
+    token: Vec<Erc20Token>,
+    signer: Signer,
+    private_keys: Vec<String>,
+    operator_selection: String,
+    operator_min_balance: f64,
+    replace_timeout: u64,
+    confirmations: u64,
+    confirmation_timeout: u64,
+    gas_multiplier: f64,
+    max_gas_price: u64,
+    max_fee_per_gas: u64,
+    max_priority_fee_per_gas: u64,
+    /// Trusted contract: operators must approve it to spend their tokens beforehand,
+    /// so anyone controlling it can take up to the allowance from the operators.
+    batch_contract: String,
+    mint_tokens: Vec<String>,
+    mint_function: String,
+    mint_abi: PathBuf,
+    /// Mint function parsed from `mint_abi` once per load of the config.
+    #[serde(skip)]
+    mint_abi_function: Option<web3::ethabi::Function>,
+}
+
+impl Default for Web3 {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            rpc_url: String::default(),
+            private_key: String::default(),
+            tokens: Vec::default(),
+            max_amount: 0,
+            token: Vec::default(),
+            signer: Signer::default(),
+            private_keys: Vec::default(),
+            operator_selection: String::default(),
+            operator_min_balance: 0.0,
+            replace_timeout: 60,
+            confirmations: 0,
+            confirmation_timeout: 120,
+            gas_multiplier: 1.2,
+            max_gas_price: 0,
+            max_fee_per_gas: 0,
+            max_priority_fee_per_gas: 0,
+            batch_contract: String::default(),
+            mint_tokens: Vec::default(),
+            mint_function: "mint".into(),
+            mint_abi: PathBuf::default(),
+            mint_abi_function: None,
+        }
+    }
 }
 
 impl Web3 {
+    /// Parses the mint function from `mint_abi` (if any token is minted).
+    fn load_mint_abi(&mut self) -> Result<()> {
+        self.mint_abi_function = None;
+        if self.enable && !self.mint_tokens.is_empty() {
+            self.mint_abi_function = Some(check_mint_abi(&self.mint_abi, &self.mint_function)?);
+        }
+        Ok(())
+    }
+
+    fn all_tokens(&self) -> Vec<Erc20Token> {
+        self.tokens
+            .iter()
+            .map(|address| Erc20Token {
+                address: address.clone(),
+                ..Default::default()
+            })
+            .chain(self.token.iter().cloned())
+            .filter(|t| t.enable)
+            .map(|mut t| {
+                if t.max_amount == 0 {
+                    t.max_amount = self.max_amount;
+                }
+                t
+            })
+            .collect()
+    }
+
+    fn check_tokens(&self) -> Result<()> {
+        if self.all_tokens().is_empty() {
+            return Err(Error::InvalidParameter(
+                "web3.tokens".into(),
+                "<empty>".into(),
+            ));
+        }
+        let mut names = self
+            .tokens
+            .iter()
+            .map(|a| a.to_lowercase())
+            .collect::<Vec<_>>();
+        for token in &self.token {
+            if ethereum::address_from_str(&token.address).is_err()
+                || names.contains(&token.address.to_lowercase())
+            {
+                return Err(Error::InvalidParameter(
+                    "web3.token.address".into(),
+                    token.address.clone(),
+                ));
+            }
+            names.push(token.address.to_lowercase());
+            if !token.alias.is_empty() {
+                if names.contains(&token.alias.to_lowercase()) {
+                    return Err(Error::InvalidParameter(
+                        "web3.token.alias".into(),
+                        token.alias.clone(),
+                    ));
+                }
+                names.push(token.alias.to_lowercase());
+            }
+            let max_amount = if token.max_amount == 0 {
+                self.max_amount
+            } else {
+                token.max_amount
+            };
+            if token.default_amount > max_amount {
+                return Err(Error::InvalidParameter(
+                    "web3.token.default_amount".into(),
+                    token.default_amount.to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn check(&self) -> Result<()> {
         if self.enable {
             if self.rpc_url.is_empty() {
@@ -370,18 +982,53 @@ impl Web3 {
                     "<empty>".into(),
                 ));
             }
-            if self.private_key.is_empty() {
+            if self.signer.is_local() && self.private_key.is_empty() {
                 return Err(Error::InvalidParameter(
                     "web3.private_key".into(),
                     "<empty>".into(),
                 ));
             }
-            if self.tokens.is_empty() {
+            self.signer.check("web3")?;
+            check_operator_selection("web3", &self.operator_selection)?;
+            if !self.batch_contract.is_empty()
+                && ethereum::address_from_str(&self.batch_contract).is_err()
+            {
                 return Err(Error::InvalidParameter(
-                    "web3.tokens".into(),
-                    "<empty>".into(),
+                    "web3.batch_contract".into(),
+                    self.batch_contract.clone(),
+                ));
+            }
+            for token in &self.mint_tokens {
+                let mut known = self
+                    .tokens
+                    .iter()
+                    .chain(self.token.iter().map(|t| &t.address));
+                if !known.any(|t| same_address(t, token)) {
+                    return Err(Error::InvalidParameter(
+                        "web3.mint_tokens".into(),
+                        token.clone(),
+                    ));
+                }
+            }
+            if self.confirmation_timeout == 0 {
+                return Err(Error::InvalidParameter(
+                    "web3.confirmation_timeout".into(),
+                    "0".into(),
+                ));
+            }
+            if self.gas_multiplier < 1.0 {
+                return Err(Error::InvalidParameter(
+                    "web3.gas_multiplier".into(),
+                    self.gas_multiplier.to_string(),
                 ));
             }
+            if self.max_fee_per_gas > 0 && self.max_priority_fee_per_gas > self.max_fee_per_gas {
+                return Err(Error::InvalidParameter(
+                    "web3.max_priority_fee_per_gas".into(),
+                    self.max_priority_fee_per_gas.to_string(),
+                ));
+            }
+            self.check_tokens()?;
             if self.max_amount == 0 {
                 return Err(Error::InvalidParameter(
                     "web3.max_amount".into(),
@@ -433,7 +1080,133 @@ impl std::fmt::Display for Web3 {
         }
         write!(f, "web3.max_amount = {}", self.max_amount)?;
         if env::var(NEON_ERC20_MAX_AMOUNT).is_ok() {
-            write!(f, " (overridden by {})", NEON_ERC20_MAX_AMOUNT)
+            writeln!(f, " (overridden by {})", NEON_ERC20_MAX_AMOUNT)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.token = [")?;
+        for (i, token) in self.token.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", token)?;
+        }
+        writeln!(f, "]")?;
+        write!(f, "web3.signer = {}", self.signer)?;
+        if env::var(FAUCET_WEB3_SIGNER_BACKEND).is_ok()
+            || env::var(FAUCET_WEB3_SIGNER_KEYSTORE).is_ok()
+            || env::var(FAUCET_WEB3_SIGNER_URL).is_ok()
+            || env::var(FAUCET_WEB3_SIGNER_PUBLIC).is_ok()
+        {
+            writeln!(f, " (overridden by FAUCET_WEB3_SIGNER_*)")?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "web3.private_keys = {:?}",
+            obfuscate_list_of_strings(&self.private_keys)
+        )?;
+        if env::var(FAUCET_WEB3_PRIVATE_KEYS).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_PRIVATE_KEYS)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "web3.operator_selection = \"{}\"",
+            self.operator_selection
+        )?;
+        if env::var(FAUCET_WEB3_OPERATOR_SELECTION).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_OPERATOR_SELECTION)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "web3.operator_min_balance = {}",
+            self.operator_min_balance
+        )?;
+        if env::var(FAUCET_WEB3_OPERATOR_MIN_BALANCE).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_OPERATOR_MIN_BALANCE)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.replace_timeout = {}", self.replace_timeout)?;
+        if env::var(FAUCET_WEB3_REPLACE_TIMEOUT).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_REPLACE_TIMEOUT)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.confirmations = {}", self.confirmations)?;
+        if env::var(FAUCET_WEB3_CONFIRMATIONS).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_CONFIRMATIONS)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "web3.confirmation_timeout = {}",
+            self.confirmation_timeout
+        )?;
+        if env::var(FAUCET_WEB3_CONFIRMATION_TIMEOUT).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_CONFIRMATION_TIMEOUT)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.gas_multiplier = {}", self.gas_multiplier)?;
+        if env::var(FAUCET_WEB3_GAS_MULTIPLIER).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_GAS_MULTIPLIER)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.max_gas_price = {}", self.max_gas_price)?;
+        if env::var(FAUCET_WEB3_MAX_GAS_PRICE).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_MAX_GAS_PRICE)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.max_fee_per_gas = {}", self.max_fee_per_gas)?;
+        if env::var(FAUCET_WEB3_MAX_FEE_PER_GAS).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_MAX_FEE_PER_GAS)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "web3.max_priority_fee_per_gas = {}",
+            self.max_priority_fee_per_gas
+        )?;
+        if env::var(FAUCET_WEB3_MAX_PRIORITY_FEE_PER_GAS).is_ok() {
+            writeln!(
+                f,
+                " (overridden by {})",
+                FAUCET_WEB3_MAX_PRIORITY_FEE_PER_GAS
+            )?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.batch_contract = \"{}\"", self.batch_contract)?;
+        if env::var(FAUCET_WEB3_BATCH_CONTRACT).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_BATCH_CONTRACT)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.mint_tokens = {:?}", self.mint_tokens)?;
+        if env::var(FAUCET_WEB3_MINT_TOKENS).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_MINT_TOKENS)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.mint_function = \"{}\"", self.mint_function)?;
+        if env::var(FAUCET_WEB3_MINT_FUNCTION).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_WEB3_MINT_FUNCTION)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "web3.mint_abi = {:?}", self.mint_abi)?;
+        if env::var(FAUCET_WEB3_MINT_ABI).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_WEB3_MINT_ABI)
         } else {
             write!(f, "")
         }
@@ -456,6 +1229,17 @@ struct Solana {
     compute_budget_additional_fee: u32, // from neon params
     operator_keyfile: PathBuf,
     max_amount: u64,
+    sol_max_amount: u64,
+    spl_mints: Vec<String>,
+    spl_max_amount: u64,
+    neon_params_refresh: u64,
+    priority_fee: u32,
+    evm_loader_elf: PathBuf,
+    signer: Signer,
+    operator_keyfiles: Vec<PathBuf>,
+    operator_selection: String,
+    operator_min_balance: f64,
+    neon_params: NeonParams,
 }
 
 impl Solana {
@@ -486,7 +1270,7 @@ impl Solana {
                 ));
             }
             use nix::NixPath as _; // to check if PathBuf is empty
-            if self.operator_keyfile.is_empty() {
+            if self.signer.is_local() && self.operator_keyfile.is_empty() {
                 return Err(Error::InvalidParameter(
                     "solana.operator_keyfile".into(),
                     "<empty>".into(),
@@ -498,11 +1282,59 @@ impl Solana {
                     "0".into(),
                 ));
             }
+            self.signer.check("solana")?;
+            check_operator_selection("solana", &self.operator_selection)?;
+            if !self.evm_loader_elf.is_empty() && !self.evm_loader_elf.exists() {
+                return Err(Error::InvalidParameter(
+                    "solana.evm_loader_elf".into(),
+                    self.evm_loader_elf.to_string_lossy().into(),
+                ));
+            }
+            let offline = self.evm_loader_elf.is_empty() && !self.neon_params.is_empty();
+            self.neon_params.check(offline)?;
+            for mint in &self.spl_mints {
+                if Pubkey::from_str(mint).is_err() {
+                    return Err(Error::InvalidParameter(
+                        "solana.spl_mints".into(),
+                        mint.clone(),
+                    ));
+                }
+            }
         }
         Ok(())
     }
 }
 
+impl Solana {
+    /// Returns parameters loaded from the EVM Loader.
+    fn neon_params(&self) -> [(&'static str, String); 6] {
+        [
+            (NEON_SEED_VERSION, self.account_seed_version.to_string()),
+            (NEON_TOKEN_MINT, self.token_mint.clone()),
+            (
+                NEON_TOKEN_MINT_DECIMALS,
+                self.token_mint_decimals.to_string(),
+            ),
+            (NEON_COMPUTE_UNITS, self.compute_budget_units.to_string()),
+            (NEON_HEAP_FRAME, self.compute_budget_heap_frame.to_string()),
+            (
+                NEON_ADDITIONAL_FEE,
+                self.compute_budget_additional_fee.to_string(),
+            ),
+        ]
+    }
+
+    /// Copies parameters loaded from the EVM Loader.
+    fn keep_neon_params(&mut self, other: &Solana) {
+        self.account_seed_version = other.account_seed_version;
+        self.token_mint = other.token_mint.clone();
+        self.token_mint_decimals = other.token_mint_decimals;
+        self.compute_budget_units = other.compute_budget_units;
+        self.compute_budget_heap_frame = other.compute_budget_heap_frame;
+        self.compute_budget_additional_fee = other.compute_budget_additional_fee;
+    }
+}
+
 impl std::fmt::Display for Solana {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "solana.enable = {}", self.enable)?;
@@ -545,35 +1377,737 @@ impl std::fmt::Display for Solana {
         }
         write!(f, "solana.max_amount = {}", self.max_amount)?;
         if env::var(NEON_ETH_MAX_AMOUNT).is_ok() {
-            write!(f, " (overridden by {})", NEON_ETH_MAX_AMOUNT)
+            writeln!(f, " (overridden by {})", NEON_ETH_MAX_AMOUNT)?;
         } else {
-            write!(f, "")
+            writeln!(f)?;
+        }
+        write!(f, "solana.sol_max_amount = {}", self.sol_max_amount)?;
+        if env::var(FAUCET_SOLANA_SOL_MAX_AMOUNT).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_SOL_MAX_AMOUNT)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "solana.spl_mints = {:?}", self.spl_mints)?;
+        if env::var(FAUCET_SOLANA_SPL_MINTS).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_SPL_MINTS)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "solana.spl_max_amount = {}", self.spl_max_amount)?;
+        if env::var(FAUCET_SOLANA_SPL_MAX_AMOUNT).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_SPL_MAX_AMOUNT)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "solana.neon_params_refresh = {}",
+            self.neon_params_refresh
+        )?;
+        if env::var(FAUCET_SOLANA_NEON_PARAMS_REFRESH).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_NEON_PARAMS_REFRESH)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "solana.priority_fee = {}", self.priority_fee)?;
+        if env::var(FAUCET_SOLANA_PRIORITY_FEE).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_PRIORITY_FEE)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "solana.evm_loader_elf = {:?}", self.evm_loader_elf)?;
+        if env::var(FAUCET_SOLANA_EVM_LOADER_ELF).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_EVM_LOADER_ELF)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "solana.signer = {}", self.signer)?;
+        if env::var(FAUCET_SOLANA_SIGNER_BACKEND).is_ok()
+            || env::var(FAUCET_SOLANA_SIGNER_KEYSTORE).is_ok()
+            || env::var(FAUCET_SOLANA_SIGNER_URL).is_ok()
+            || env::var(FAUCET_SOLANA_SIGNER_PUBLIC).is_ok()
+        {
+            writeln!(f, " (overridden by FAUCET_SOLANA_SIGNER_*)")?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "solana.operator_keyfiles = {:?}", self.operator_keyfiles)?;
+        if env::var(FAUCET_SOLANA_OPERATOR_KEYFILES).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_OPERATOR_KEYFILES)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "solana.operator_selection = \"{}\"",
+            self.operator_selection
+        )?;
+        if env::var(FAUCET_SOLANA_OPERATOR_SELECTION).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_OPERATOR_SELECTION)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "solana.operator_min_balance = {}",
+            self.operator_min_balance
+        )?;
+        if env::var(FAUCET_SOLANA_OPERATOR_MIN_BALANCE).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_SOLANA_OPERATOR_MIN_BALANCE)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "solana.neon_params = {}", self.neon_params)
+    }
+}
+
+pub const SELECTION_ROUND_ROBIN: &str = "round_robin";
+pub const SELECTION_BALANCE: &str = "balance";
+
+/// Checks the way of choosing an operator for an airdrop.
+fn check_operator_selection(section: &str, selection: &str) -> Result<()> {
+    match selection {
+        "" | SELECTION_ROUND_ROBIN | SELECTION_BALANCE => Ok(()),
+        _ => Err(Error::InvalidParameter(
+            format!("{}.operator_selection", section),
+            selection.into(),
+        )),
+    }
+}
+
+/// Compares Ethereum addresses regardless of case (checksum); invalid addresses differ.
+fn same_address(a: &str, b: &str) -> bool {
+    match (ethereum::address_from_str(a), ethereum::address_from_str(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Checks the ABI file has the mint function taking `(address, uint256)` and returns it.
+fn check_mint_abi(file: &Path, function: &str) -> Result<web3::ethabi::Function> {
+    use web3::ethabi::ParamType;
+    let abi = std::fs::read(file).map_err(|e| Error::Read(e, file.into()))?;
+    let contract = web3::ethabi::Contract::load(&abi[..])
+        .map_err(|_| Error::InvalidParameter("web3.mint_abi".into(), file.display().to_string()))?;
+    let found = contract.functions_by_name(function).ok().and_then(|f| {
+        f.iter().find(|f| {
+            f.inputs.len() == 2
+                && f.inputs[0].kind == ParamType::Address
+                && f.inputs[1].kind == ParamType::Uint(256)
+        })
+    });
+    found
+        .cloned()
+        .ok_or_else(|| Error::InvalidParameter("web3.mint_function".into(), function.into()))
+}
+
+pub const SIGNER_LOCAL: &str = "local";
+pub const SIGNER_KEYSTORE: &str = "keystore";
+pub const SIGNER_ENV: &str = "env";
+pub const SIGNER_REMOTE: &str = "remote";
+
+/// Represents the source of an operator's private key.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Signer {
+    /// One of "local" (default), "keystore", "env" or "remote".
+    pub backend: String,
+    /// Encrypted keystore file (for "keystore").
+    pub keystore: PathBuf,
+    /// Environment variable with passphrase of the keystore (for "keystore").
+    pub passphrase_env: String,
+    /// Environment variable with the private key (for "env").
+    pub key_env: String,
+    /// URL of the remote signer (for "remote").
+    pub url: String,
+    /// Public key or address of the remote signer's key (for "remote").
+    pub public: String,
+}
+
+impl Signer {
+    /// Checks if the key is defined directly in the section.
+    pub fn is_local(&self) -> bool {
+        self.backend.is_empty() || self.backend == SIGNER_LOCAL
+    }
+
+    fn check(&self, section: &str) -> Result<()> {
+        let required = match self.backend.as_str() {
+            "" | SIGNER_LOCAL => vec![],
+            SIGNER_KEYSTORE => vec![
+                ("keystore", self.keystore.as_os_str().is_empty()),
+                ("passphrase_env", self.passphrase_env.is_empty()),
+            ],
+            SIGNER_ENV => vec![("key_env", self.key_env.is_empty())],
+            SIGNER_REMOTE => vec![
+                ("url", self.url.is_empty()),
+                ("public", self.public.is_empty()),
+            ],
+            _ => {
+                return Err(Error::InvalidParameter(
+                    format!("{}.signer.backend", section),
+                    self.backend.clone(),
+                ))
+            }
+        };
+        for (name, empty) in required {
+            if empty {
+                return Err(Error::InvalidParameter(
+                    format!("{}.signer.{}", section, name),
+                    "<empty>".into(),
+                ));
+            }
         }
-    }
-}
-
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
-#[serde(default)]
-#[serde(deny_unknown_fields)]
-struct Faucet {
-    rpc: Rpc,
-    web3: Web3,
-    solana: Solana,
-}
-
-impl Faucet {
-    /// Constructs config from a file.
-    fn load(&mut self, file: &Path) -> Result<()> {
-        let text = std::fs::read_to_string(file).map_err(|e| Error::Read(e, file.to_owned()))?;
-        *self = toml::from_str(&text).map_err(|e| Error::Parse(e, file.to_owned()))?;
-        Ok(())
-    }
-
-    /// Performs preliminary check of the config.
-    fn check(&self) -> Result<()> {
-        self.rpc.check()?;
-        self.web3.check()?;
-        self.solana.check()?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.backend.as_str() {
+            SIGNER_KEYSTORE => write!(
+                f,
+                "{{ backend = \"{}\", keystore = {:?}, passphrase_env = \"{}\" }}",
+                self.backend, self.keystore, self.passphrase_env
+            ),
+            SIGNER_ENV => write!(
+                f,
+                "{{ backend = \"{}\", key_env = \"{}\" }}",
+                self.backend, self.key_env
+            ),
+            SIGNER_REMOTE => write!(
+                f,
+                "{{ backend = \"{}\", url = \"{}\", public = \"{}\" }}",
+                self.backend, self.url, self.public
+            ),
+            _ => write!(f, "{{ backend = \"{}\" }}", SIGNER_LOCAL),
+        }
+    }
+}
+
+/// Represents an ERC20 token of the `[[web3.token]]` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Erc20Token {
+    pub address: String,
+    /// Name of the token in requests (like a symbol).
+    pub alias: String,
+    /// Largest amount per request (`web3.max_amount` if zero).
+    pub max_amount: u64,
+    /// Amount for requests without one (amount is required if zero).
+    pub default_amount: u64,
+    pub enable: bool,
+}
+
+impl Default for Erc20Token {
+    fn default() -> Self {
+        Self {
+            address: String::default(),
+            alias: String::default(),
+            max_amount: 0,
+            default_amount: 0,
+            enable: true,
+        }
+    }
+}
+
+impl std::fmt::Display for Erc20Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ address = \"{}\", alias = \"{}\", max_amount = {}, default_amount = {}, enable = {} }}",
+            obfuscate_string(&self.address),
+            self.alias,
+            self.max_amount,
+            self.default_amount,
+            self.enable
+        )
+    }
+}
+
+/// Represents NEON parameters of the EVM Loader defined in the config.
+/// Missing parameters are read from the EVM Loader.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+struct NeonParams {
+    seed_version: Option<u8>,
+    token_mint: Option<String>,
+    token_mint_decimals: Option<u8>,
+    compute_units: Option<u32>,
+    heap_frame: Option<u32>,
+    additional_fee: Option<u32>,
+}
+
+/// Largest number of compute units of a transaction.
+const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+/// Smallest and largest heap frame of a transaction (multiple of 1024 bytes).
+const MIN_HEAP_FRAME: u32 = 32 * 1024;
+const MAX_HEAP_FRAME: u32 = 256 * 1024;
+
+impl NeonParams {
+    fn is_empty(&self) -> bool {
+        *self == NeonParams::default()
+    }
+
+    /// Checks the values; `offline` table must define all parameters of the token.
+    fn check(&self, offline: bool) -> Result<()> {
+        let missing = |name: &str| {
+            Error::InvalidParameter(format!("solana.neon_params.{}", name), "<empty>".into())
+        };
+        if offline {
+            if self.seed_version.is_none() {
+                return Err(missing("seed_version"));
+            }
+            if self.token_mint.is_none() {
+                return Err(missing("token_mint"));
+            }
+            if self.token_mint_decimals.is_none() {
+                return Err(missing("token_mint_decimals"));
+            }
+        }
+        if let Some(ref mint) = self.token_mint {
+            if Pubkey::from_str(mint).is_err() {
+                return Err(Error::InvalidParameter(
+                    "solana.neon_params.token_mint".into(),
+                    mint.clone(),
+                ));
+            }
+        }
+        if let Some(decimals) = self.token_mint_decimals {
+            if decimals == 0 || 10u64.checked_pow(decimals.into()).is_none() {
+                return Err(Error::InvalidParameter(
+                    "solana.neon_params.token_mint_decimals".into(),
+                    decimals.to_string(),
+                ));
+            }
+        }
+        if let Some(units) = self.compute_units {
+            if units > MAX_COMPUTE_UNITS {
+                return Err(Error::InvalidParameter(
+                    "solana.neon_params.compute_units".into(),
+                    units.to_string(),
+                ));
+            }
+        }
+        if let Some(heap_frame) = self.heap_frame {
+            let valid =
+                (MIN_HEAP_FRAME..=MAX_HEAP_FRAME).contains(&heap_frame) && heap_frame % 1024 == 0;
+            if heap_frame != 0 && !valid {
+                return Err(Error::InvalidParameter(
+                    "solana.neon_params.heap_frame".into(),
+                    heap_frame.to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns defined parameters named as symbols of the EVM Loader.
+    fn to_map(&self) -> HashMap<String, String> {
+        [
+            (NEON_SEED_VERSION, self.seed_version.map(|v| v.to_string())),
+            (NEON_TOKEN_MINT, self.token_mint.clone()),
+            (
+                NEON_TOKEN_MINT_DECIMALS,
+                self.token_mint_decimals.map(|v| v.to_string()),
+            ),
+            (
+                NEON_COMPUTE_UNITS,
+                self.compute_units.map(|v| v.to_string()),
+            ),
+            (NEON_HEAP_FRAME, self.heap_frame.map(|v| v.to_string())),
+            (
+                NEON_ADDITIONAL_FEE,
+                self.additional_fee.map(|v| v.to_string()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.to_owned(), value?)))
+        .collect()
+    }
+}
+
+impl std::fmt::Display for NeonParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut params = vec![];
+        if let Some(v) = self.seed_version {
+            params.push(format!("seed_version = {}", v));
+        }
+        if let Some(ref v) = self.token_mint {
+            params.push(format!("token_mint = {:?}", v));
+        }
+        if let Some(v) = self.token_mint_decimals {
+            params.push(format!("token_mint_decimals = {}", v));
+        }
+        if let Some(v) = self.compute_units {
+            params.push(format!("compute_units = {}", v));
+        }
+        if let Some(v) = self.heap_frame {
+            params.push(format!("heap_frame = {}", v));
+        }
+        if let Some(v) = self.additional_fee {
+            params.push(format!("additional_fee = {}", v));
+        }
+        write!(f, "{{ {} }}", params.join(", "))
+    }
+}
+
+/// Represents caps applied to a single client (wallet or IP) within the window.
+/// Zero value means "unlimited".
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Quota {
+    /// Number of airdrop requests.
+    pub requests: u64,
+    /// Cumulative amount of NEON (in whole tokens).
+    pub neon: u64,
+    /// Cumulative amount of ERC20 tokens (in whole tokens).
+    pub erc20: u64,
+    /// Cumulative amount of SOL (in whole tokens).
+    pub sol: u64,
+    /// Cumulative amount of SPL tokens (in whole tokens).
+    pub spl: u64,
+}
+
+impl std::fmt::Display for Quota {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ requests = {}, neon = {}, erc20 = {}, sol = {}, spl = {} }}",
+            self.requests, self.neon, self.erc20, self.sol, self.spl
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+struct Limits {
+    enable: bool,
+    window: u64,
+    wallet: Quota,
+    ip: Quota,
+    trusted_proxies: Vec<String>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            window: 86400,
+            wallet: Quota::default(),
+            ip: Quota::default(),
+            trusted_proxies: vec![],
+        }
+    }
+}
+
+impl Limits {
+    fn check(&self) -> Result<()> {
+        if self.enable && self.window == 0 {
+            return Err(Error::InvalidParameter("limits.window".into(), "0".into()));
+        }
+        for ip in &self.trusted_proxies {
+            if ip.parse::<IpAddr>().is_err() {
+                return Err(Error::InvalidParameter(
+                    "limits.trusted_proxies".into(),
+                    ip.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Limits {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "limits.enable = {}", self.enable)?;
+        if env::var(FAUCET_LIMITS_ENABLE).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_LIMITS_ENABLE)?;
+        } else {
+            write!(f, "")?;
+        }
+        if !self.enable {
+            return Ok(());
+        }
+        writeln!(f)?;
+        write!(f, "limits.window = {}", self.window)?;
+        if env::var(FAUCET_LIMITS_WINDOW).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_LIMITS_WINDOW)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "limits.wallet = {}", self.wallet)?;
+        if env::var(FAUCET_LIMITS_WALLET_REQUESTS).is_ok()
+            || env::var(FAUCET_LIMITS_WALLET_NEON).is_ok()
+            || env::var(FAUCET_LIMITS_WALLET_ERC20).is_ok()
+            || env::var(FAUCET_LIMITS_WALLET_SOL).is_ok()
+            || env::var(FAUCET_LIMITS_WALLET_SPL).is_ok()
+        {
+            writeln!(f, " (overridden by FAUCET_LIMITS_WALLET_*)")?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "limits.ip = {}", self.ip)?;
+        if env::var(FAUCET_LIMITS_IP_REQUESTS).is_ok()
+            || env::var(FAUCET_LIMITS_IP_NEON).is_ok()
+            || env::var(FAUCET_LIMITS_IP_ERC20).is_ok()
+            || env::var(FAUCET_LIMITS_IP_SOL).is_ok()
+            || env::var(FAUCET_LIMITS_IP_SPL).is_ok()
+        {
+            writeln!(f, " (overridden by FAUCET_LIMITS_IP_*)")?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "limits.trusted_proxies = {:?}", self.trusted_proxies)?;
+        if env::var(FAUCET_LIMITS_TRUSTED_PROXIES).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_LIMITS_TRUSTED_PROXIES)
+        } else {
+            write!(f, "")
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+struct Storage {
+    enable: bool,
+    path: PathBuf,
+}
+
+impl Storage {
+    fn check(&self) -> Result<()> {
+        use nix::NixPath as _; // to check if PathBuf is empty
+        if self.enable && self.path.is_empty() {
+            return Err(Error::InvalidParameter(
+                "storage.path".into(),
+                "<empty>".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Storage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "storage.enable = {}", self.enable)?;
+        if env::var(FAUCET_STORAGE_ENABLE).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_STORAGE_ENABLE)?;
+        } else {
+            write!(f, "")?;
+        }
+        if !self.enable {
+            return Ok(());
+        }
+        writeln!(f)?;
+        write!(f, "storage.path = {:?}", self.path)?;
+        if env::var(FAUCET_STORAGE_PATH).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_STORAGE_PATH)
+        } else {
+            write!(f, "")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+struct Captcha {
+    enable: bool,
+    verify_url: String,
+    secret: String,
+    endpoints: Vec<String>,
+}
+
+impl Default for Captcha {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            verify_url: String::default(),
+            secret: String::default(),
+            endpoints: vec![
+                "request_neon_in_galans".into(),
+                "request_neon".into(),
+                "request_erc20".into(),
+                "request_sol".into(),
+                "request_spl".into(),
+            ],
+        }
+    }
+}
+
+impl Captcha {
+    fn check(&self) -> Result<()> {
+        if self.enable {
+            if self.verify_url.is_empty() {
+                return Err(Error::InvalidParameter(
+                    "captcha.verify_url".into(),
+                    "<empty>".into(),
+                ));
+            }
+            if self.secret.is_empty() {
+                return Err(Error::InvalidParameter(
+                    "captcha.secret".into(),
+                    "<empty>".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Captcha {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "captcha.enable = {}", self.enable)?;
+        if env::var(FAUCET_CAPTCHA_ENABLE).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_CAPTCHA_ENABLE)?;
+        } else {
+            write!(f, "")?;
+        }
+        if !self.enable {
+            return Ok(());
+        }
+        writeln!(f)?;
+        write!(f, "captcha.verify_url = \"{}\"", self.verify_url)?;
+        if env::var(FAUCET_CAPTCHA_VERIFY_URL).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_CAPTCHA_VERIFY_URL)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "captcha.secret = \"{}\"", obfuscate_string(&self.secret))?;
+        if env::var(FAUCET_CAPTCHA_SECRET).is_ok() {
+            writeln!(f, " (overridden by {})", FAUCET_CAPTCHA_SECRET)?;
+        } else {
+            writeln!(f)?;
+        }
+        write!(f, "captcha.endpoints = {:?}", self.endpoints)?;
+        if env::var(FAUCET_CAPTCHA_ENDPOINTS).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_CAPTCHA_ENDPOINTS)
+        } else {
+            write!(f, "")
+        }
+    }
+}
+
+/// Represents an API key with its own limits.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    /// Name of the key owner (for logs).
+    pub name: String,
+    /// SHA-256 hash (hex) of the key.
+    pub hash: String,
+    /// Largest amount (in whole tokens) per request; zero means the default.
+    pub max_amount: u64,
+    /// Caps within 24 hours; zero value means "unlimited".
+    pub daily_quota: Quota,
+    /// Allowed endpoints; empty list means all airdrop endpoints.
+    pub endpoints: Vec<String>,
+}
+
+impl ApiKey {
+    /// Checks if the key allows the endpoint.
+    pub fn allows(&self, endpoint: &str) -> bool {
+        self.endpoints.is_empty() || self.endpoints.iter().any(|e| e == endpoint)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+struct Auth {
+    anonymous: bool,
+    keys: Vec<ApiKey>,
+}
+
+impl Default for Auth {
+    fn default() -> Self {
+        Self {
+            anonymous: true,
+            keys: Vec::default(),
+        }
+    }
+}
+
+impl Auth {
+    fn check(&self) -> Result<()> {
+        if !self.anonymous && self.keys.is_empty() {
+            return Err(Error::InvalidParameter(
+                "auth.keys".into(),
+                "<empty>".into(),
+            ));
+        }
+        for key in &self.keys {
+            if key.name.is_empty() {
+                return Err(Error::InvalidParameter(
+                    "auth.keys.name".into(),
+                    "<empty>".into(),
+                ));
+            }
+            if key.hash.len() != 64 || !key.hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(Error::InvalidParameter(
+                    format!("auth.keys.hash ({})", key.name),
+                    key.hash.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "auth.anonymous = {}", self.anonymous)?;
+        if env::var(FAUCET_AUTH_ANONYMOUS).is_ok() {
+            write!(f, " (overridden by {})", FAUCET_AUTH_ANONYMOUS)?;
+        }
+        for key in &self.keys {
+            writeln!(f)?;
+            write!(
+                f,
+                "auth.keys.{} = {{ max_amount = {}, daily_quota = {}, endpoints = {:?} }}",
+                key.name, key.max_amount, key.daily_quota, key.endpoints
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+struct Faucet {
+    rpc: Rpc,
+    web3: Web3,
+    solana: Solana,
+    limits: Limits,
+    storage: Storage,
+    captcha: Captcha,
+    auth: Auth,
+}
+
+impl Faucet {
+    /// Constructs config from a file.
+    fn load(&mut self, file: &Path) -> Result<()> {
+        let text = std::fs::read_to_string(file).map_err(|e| Error::Read(e, file.to_owned()))?;
+        *self = toml::from_str(&text).map_err(|e| Error::Parse(e, file.to_owned()))?;
+        Ok(())
+    }
+
+    /// Performs preliminary check of the config.
+    fn check(&self) -> Result<()> {
+        self.rpc.check()?;
+        self.web3.check()?;
+        self.solana.check()?;
+        self.limits.check()?;
+        self.storage.check()?;
+        self.captcha.check()?;
+        self.auth.check()?;
         Ok(())
     }
 }
@@ -582,7 +2116,11 @@ impl std::fmt::Display for Faucet {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         writeln!(f, "{}", self.rpc)?;
         writeln!(f, "{}", self.web3)?;
-        write!(f, "{}", self.solana)
+        writeln!(f, "{}", self.solana)?;
+        writeln!(f, "{}", self.limits)?;
+        writeln!(f, "{}", self.storage)?;
+        writeln!(f, "{}", self.captcha)?;
+        write!(f, "{}", self.auth)
     }
 }
 
@@ -794,35 +2332,58 @@ fn test_trim_first_and_last_chars() {
 
 /// Reads NEON parameters from the EVM Loader account.
 pub async fn load_neon_params() -> Result<()> {
-    let params = tokio::task::spawn_blocking(move || -> Result<HashMap<String, String>> {
-        let client = RpcClient::new_with_commitment(solana_url(), solana_commitment());
-        read_neon_parameters_from_account(client)
+    let (elf, table) = {
+        let config = CONFIG.read().unwrap();
+        (
+            config.solana.evm_loader_elf.clone(),
+            config.solana.neon_params.clone(),
+        )
+    };
+
+    let offline = !table.is_empty();
+    let mut params = tokio::task::spawn_blocking(move || -> Result<HashMap<String, String>> {
+        use nix::NixPath as _; // to check if PathBuf is empty
+        if !elf.is_empty() {
+            read_neon_parameters_from_file(&elf)
+        } else if offline {
+            Ok(HashMap::new())
+        } else {
+            let client = RpcClient::new_with_commitment(solana_url(), solana_commitment());
+            read_neon_parameters_from_account(client)
+        }
     })
-    .await
-    .expect("Solana does not respond")?;
+    .await??;
+    params.extend(table.to_map());
 
+    let mut solana = CONFIG.read().unwrap().solana.clone();
     for (param_name, val) in &params {
         match param_name.as_ref() {
-            NEON_SEED_VERSION => {
-                CONFIG.write().unwrap().solana.account_seed_version = val.parse::<u8>()?
-            }
-            NEON_TOKEN_MINT => CONFIG.write().unwrap().solana.token_mint = val.into(),
-            NEON_TOKEN_MINT_DECIMALS => {
-                CONFIG.write().unwrap().solana.token_mint_decimals = val.parse::<u8>()?
-            }
-            NEON_COMPUTE_UNITS => {
-                CONFIG.write().unwrap().solana.compute_budget_units = val.parse::<u32>()?
-            }
-            NEON_HEAP_FRAME => {
-                CONFIG.write().unwrap().solana.compute_budget_heap_frame = val.parse::<u32>()?
-            }
-            NEON_ADDITIONAL_FEE => {
-                CONFIG.write().unwrap().solana.compute_budget_additional_fee = val.parse::<u32>()?
-            }
+            NEON_SEED_VERSION => solana.account_seed_version = val.parse::<u8>()?,
+            NEON_TOKEN_MINT => solana.token_mint = val.into(),
+            NEON_TOKEN_MINT_DECIMALS => solana.token_mint_decimals = val.parse::<u8>()?,
+            NEON_COMPUTE_UNITS => solana.compute_budget_units = val.parse::<u32>()?,
+            NEON_HEAP_FRAME => solana.compute_budget_heap_frame = val.parse::<u32>()?,
+            NEON_ADDITIONAL_FEE => solana.compute_budget_additional_fee = val.parse::<u32>()?,
             _ => {}
         }
     }
 
+    let mut config = CONFIG.write().unwrap();
+    let loaded = config.solana.account_seed_version != 0;
+    let old = config.solana.neon_params();
+    for (new, old) in solana.neon_params().iter().zip(old.iter()) {
+        if loaded && new != old {
+            info!(
+                "{} NEON parameter {} changed: {:?} -> {:?}",
+                id::default(),
+                new.0,
+                old.1,
+                new.1
+            );
+        }
+    }
+    config.solana.keep_neon_params(&solana);
+
     Ok(())
 }
 
@@ -836,7 +2397,7 @@ fn read_neon_parameters_from_account(client: RpcClient) -> Result<HashMap<String
     })?;
 
     if account.owner == bpf_loader::id() || account.owner == bpf_loader_deprecated::id() {
-        Ok(read_elf_parameters(&account.data))
+        read_elf_parameters(&account.data)
     } else if account.owner == bpf_loader_upgradeable::id() {
         if let Ok(UpgradeableLoaderState::Program {
             programdata_address,
@@ -848,8 +2409,8 @@ fn read_neon_parameters_from_account(client: RpcClient) -> Result<HashMap<String
 
             if let Ok(UpgradeableLoaderState::ProgramData { .. }) = programdata_account.state() {
                 let offset = UpgradeableLoaderState::programdata_data_offset().unwrap_or(0);
-                let program_data = &programdata_account.data[offset..];
-                Ok(read_elf_parameters(program_data))
+                let program_data = programdata_account.data.get(offset..).unwrap_or_default();
+                read_elf_parameters(program_data)
             } else {
                 Err(Error::InvalidAssociatedPda(
                     programdata_address,
@@ -858,8 +2419,8 @@ fn read_neon_parameters_from_account(client: RpcClient) -> Result<HashMap<String
             }
         } else if let Ok(UpgradeableLoaderState::Buffer { .. }) = account.state() {
             let offset = UpgradeableLoaderState::buffer_data_offset().unwrap_or(0);
-            let program_data = &account.data[offset..];
-            Ok(read_elf_parameters(program_data))
+            let program_data = account.data.get(offset..).unwrap_or_default();
+            read_elf_parameters(program_data)
         } else {
             Err(Error::AccountIsNotUpgradeable(evm_loader_id))
         }
@@ -868,32 +2429,278 @@ fn read_neon_parameters_from_account(client: RpcClient) -> Result<HashMap<String
     }
 }
 
-fn read_elf_parameters(account_data: &[u8]) -> HashMap<String, String> {
+fn read_neon_parameters_from_file(file: &Path) -> Result<HashMap<String, String>> {
+    let data = std::fs::read(file).map_err(|e| Error::Read(e, file.to_owned()))?;
+    read_elf_parameters(&data)
+}
+
+fn read_elf_parameters(account_data: &[u8]) -> Result<HashMap<String, String>> {
+    let elf = goblin::elf::Elf::parse(account_data)?;
+
+    let symbols = elf
+        .dynsyms
+        .iter()
+        .map(|sym| (sym, &elf.dynstrtab))
+        .chain(elf.syms.iter().map(|sym| (sym, &elf.strtab)));
+
     let mut result = HashMap::new();
-    let elf = goblin::elf::Elf::parse(account_data).expect("Unable to parse ELF file");
-
-    elf.dynsyms.iter().for_each(|sym| {
-        let name = String::from(&elf.dynstrtab[sym.st_name]);
-        if name.starts_with("NEON") {
-            let end = account_data.len();
-            let from: usize = usize::try_from(sym.st_value)
-                .unwrap_or_else(|_| panic!("Unable to cast usize from u64:{:?}", sym.st_value));
-            let to: usize = usize::try_from(sym.st_value + sym.st_size).unwrap_or_else(|err| {
-                panic!(
-                    "Unable to cast usize from u64:{:?}. Error: {}",
-                    sym.st_value + sym.st_size,
-                    err
-                )
-            });
-            if to < end && from < end {
-                let buf = &account_data[from..to];
-                let value = std::str::from_utf8(buf).unwrap();
-                result.insert(name, String::from(value));
-            } else {
-                panic!("{} is out of bounds", name);
+    for (sym, strtab) in symbols {
+        let name = match strtab.get_at(sym.st_name) {
+            Some(name) if name.starts_with("NEON") => name,
+            _ => continue,
+        };
+        if result.contains_key(name) {
+            continue;
+        }
+        match read_elf_symbol(&elf, account_data, &sym) {
+            Some(value) => {
+                result.insert(name.to_owned(), value);
             }
+            None => warn!(
+                "{} Skipped unreadable parameter {} of EVM Loader",
+                id::default(),
+                name
+            ),
         }
+    }
+
+    if result.is_empty() {
+        return Err(Error::NeonParamsNotFound);
+    }
+    Ok(result)
+}
+
+/// Reads value of the symbol as UTF-8 string without trailing NULs.
+fn read_elf_symbol(elf: &goblin::elf::Elf, data: &[u8], sym: &goblin::elf::Sym) -> Option<String> {
+    let from = elf_file_offset(elf, sym.st_value)?;
+    let to = from.checked_add(usize::try_from(sym.st_size).ok()?)?;
+    let value = std::str::from_utf8(data.get(from..to)?).ok()?;
+    Some(value.trim_end_matches('\0').to_owned())
+}
+
+/// Translates virtual address into offset in the ELF file.
+fn elf_file_offset(elf: &goblin::elf::Elf, vaddr: u64) -> Option<usize> {
+    use goblin::elf::program_header::PT_LOAD;
+    use goblin::elf::section_header::SHT_NOBITS;
+
+    let section = elf.section_headers.iter().find(|s| {
+        s.sh_type != SHT_NOBITS
+            && s.sh_addr != 0
+            && s.sh_addr <= vaddr
+            && vaddr - s.sh_addr < s.sh_size
     });
+    if let Some(s) = section {
+        return usize::try_from(vaddr - s.sh_addr + s.sh_offset).ok();
+    }
+
+    let segment = elf
+        .program_headers
+        .iter()
+        .find(|p| p.p_type == PT_LOAD && p.p_vaddr <= vaddr && vaddr - p.p_vaddr < p.p_filesz);
+    if let Some(p) = segment {
+        return usize::try_from(vaddr - p.p_vaddr + p.p_offset).ok();
+    }
+
+    // Legacy layout: virtual addresses are file offsets
+    usize::try_from(vaddr).ok()
+}
 
-    result
+#[test]
+fn test_read_elf_parameters() {
+    let params =
+        read_elf_parameters(include_bytes!("../tests/fixtures/evm_loader_v1.elf")).unwrap();
+    assert_eq!(params.len(), 6);
+    assert_eq!(params[NEON_SEED_VERSION], "1");
+    assert_eq!(
+        params[NEON_TOKEN_MINT],
+        "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g"
+    );
+    assert_eq!(params[NEON_TOKEN_MINT_DECIMALS], "9");
+    assert_eq!(params[NEON_COMPUTE_UNITS], "500000");
+    assert_eq!(params[NEON_HEAP_FRAME], "262144");
+    assert_eq!(params[NEON_ADDITIONAL_FEE], "0");
+
+    let params =
+        read_elf_parameters(include_bytes!("../tests/fixtures/evm_loader_v2.elf")).unwrap();
+    assert_eq!(params.len(), 6);
+    assert_eq!(params[NEON_SEED_VERSION], "3");
+    assert_eq!(
+        params[NEON_TOKEN_MINT],
+        "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g"
+    );
+    assert_eq!(params[NEON_TOKEN_MINT_DECIMALS], "9");
+    assert_eq!(params[NEON_COMPUTE_UNITS], "1400000");
+    assert_eq!(params[NEON_ADDITIONAL_FEE], "5000");
+    assert!(!params.contains_key("NEON_PKG_VERSION"));
+}
+
+#[test]
+fn test_neon_params_to_map() {
+    let table: NeonParams = toml::from_str(
+        r#"
+        seed_version = 1
+        token_mint = "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g"
+        heap_frame = 262144
+        "#,
+    )
+    .unwrap();
+    let mut params =
+        read_neon_parameters_from_file(Path::new("tests/fixtures/evm_loader_v2.elf")).unwrap();
+    params.extend(table.to_map());
+    assert_eq!(params[NEON_SEED_VERSION], "1");
+    assert_eq!(params[NEON_HEAP_FRAME], "262144");
+    assert_eq!(params[NEON_COMPUTE_UNITS], "1400000");
+    assert_eq!(
+        table.to_string(),
+        r#"{ seed_version = 1, token_mint = "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g", heap_frame = 262144 }"#
+    );
+}
+
+#[test]
+fn test_read_elf_parameters_malformed() {
+    let data = include_bytes!("../tests/fixtures/evm_loader_v1.elf");
+    assert!(matches!(
+        read_elf_parameters(&data[..100]),
+        Err(Error::ParseElf(_))
+    ));
+    assert!(matches!(
+        read_elf_parameters(b"not an elf"),
+        Err(Error::ParseElf(_))
+    ));
+}
+
+#[test]
+fn test_same_address() {
+    let lower = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+    assert!(same_address(
+        lower,
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+    ));
+    assert!(same_address(
+        lower,
+        "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+    ));
+    assert!(!same_address(
+        lower,
+        "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359"
+    ));
+    assert!(!same_address("USDT", "usdt"));
+}
+
+#[test]
+fn test_check_mint_abi() {
+    let file = Path::new("erc20/Mintable.abi");
+    let function = check_mint_abi(file, "mint").unwrap();
+    assert_eq!(function.signature(), "mint(address,uint256)");
+    assert!(check_mint_abi(file, "burn").is_err());
+    assert!(check_mint_abi(Path::new("erc20/ERC20.abi"), "transfer").is_ok());
+    assert!(check_mint_abi(Path::new("erc20/ERC20.abi"), "approve").is_ok());
+    assert!(check_mint_abi(Path::new("erc20/ERC20.abi"), "balanceOf").is_err());
+    assert!(check_mint_abi(Path::new("erc20/Missing.abi"), "mint").is_err());
+}
+
+#[test]
+fn test_web3_tokens() {
+    let web3: Web3 = toml::from_str(
+        r#"
+        tokens = ["0x00000000000000000000000000000000CafeBabe"]
+        max_amount = 1000
+
+        [[token]]
+        address = "0x00000000000000000000000000000000DeadBeef"
+        alias = "USDC"
+        max_amount = 100
+        default_amount = 10
+
+        [[token]]
+        address = "0x00000000000000000000000000000000Deadc0de"
+        enable = false
+        "#,
+    )
+    .unwrap();
+    assert!(web3.check_tokens().is_ok());
+    let tokens = web3.all_tokens();
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].max_amount, 1000);
+    assert_eq!(tokens[1].alias, "USDC");
+    assert_eq!(tokens[1].max_amount, 100);
+
+    let mut web3 = web3;
+    web3.token[1].alias = "usdc".into();
+    assert!(web3.check_tokens().is_err());
+    web3.token[1].alias = String::default();
+    web3.token[0].default_amount = 101;
+    assert!(web3.check_tokens().is_err());
+}
+
+#[test]
+fn test_reload() {
+    let file = env::temp_dir().join(format!("faucet-test-reload-{}.conf", std::process::id()));
+    std::fs::write(&file, "[rpc]\nbind = \"0.0.0.0\"\nport = 4444\n").unwrap();
+    load(&file).unwrap();
+    CONFIG.write().unwrap().solana.token_mint_decimals = 9; // as loaded from the EVM Loader
+
+    std::fs::write(&file, "[rpc]\nbind = \"0.0.0.0\"\nport = 5555\n").unwrap();
+    assert!(reload(&file).is_ok());
+    assert_eq!(rpc_port(), 5555);
+    assert_eq!(CONFIG.read().unwrap().solana.token_mint_decimals, 9);
+
+    let before = CONFIG.read().unwrap().to_string();
+    std::fs::write(&file, "[rpc]\nport = \"broken\"\n").unwrap();
+    assert!(matches!(reload(&file), Err(Error::Parse(_, _))));
+    std::fs::write(
+        &file,
+        "[rpc]\nbind = \"0.0.0.0\"\nport = 6666\n[limits]\nenable = true\nwindow = 0\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        reload(&file),
+        Err(Error::InvalidParameter(p, _)) if p == "limits.window"
+    ));
+    assert_eq!(CONFIG.read().unwrap().to_string(), before);
+    assert_eq!(rpc_port(), 5555);
+    assert_eq!(CONFIG.read().unwrap().solana.token_mint_decimals, 9);
+
+    std::fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn test_check_neon_params() {
+    let params: NeonParams = toml::from_str(
+        r#"
+        seed_version = 1
+        token_mint = "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g"
+        "#,
+    )
+    .unwrap();
+    assert!(params.check(false).is_ok());
+    assert!(matches!(
+        params.check(true),
+        Err(Error::InvalidParameter(p, _)) if p == "solana.neon_params.token_mint_decimals"
+    ));
+
+    let params = NeonParams {
+        token_mint_decimals: Some(9),
+        heap_frame: Some(262144),
+        compute_units: Some(500000),
+        ..params
+    };
+    assert!(params.check(true).is_ok());
+    let invalid = [
+        NeonParams {
+            token_mint_decimals: Some(0),
+            ..params.clone()
+        },
+        NeonParams {
+            heap_frame: Some(1000),
+            ..params.clone()
+        },
+        NeonParams {
+            compute_units: Some(MAX_COMPUTE_UNITS + 1),
+            ..params.clone()
+        },
+    ];
+    for p in invalid {
+        assert!(p.check(true).is_err());
+    }
 }
@@ -1,101 +1,537 @@
 //! Faucet ERC20 tokens module.
 
+use std::time::{Duration, Instant};
+
 use eyre::{eyre, Result};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use secp256k1::SecretKey;
 use web3::api::Eth;
+use web3::contract::tokens::Tokenize as _;
 use web3::contract::{Contract, Options};
-use web3::signing::Key;
-use web3::types::U256;
+use web3::types::{CallRequest, H256, U256};
 use web3::Transport;
 
-use crate::{config, ethereum, id::ReqId};
+use crate::error::Error;
+use crate::signer::EthereumSigner;
+use crate::{config, ethereum, gas, id::ReqId, jobs, ledger, metrics, nonce, operators, pool};
+
+/// Time to reuse queried balances of the operators.
+const BALANCES_TTL: Duration = Duration::from_secs(30);
 
 /// Represents packet of information needed for an airdrop operation.
 #[derive(Debug, serde::Deserialize)]
 pub struct Airdrop {
     /// Ethereum address of the recipient.
-    wallet: String,
+    pub wallet: String,
     /// ERC20 Token address. If missing, do all tokens.
     token_addr: Option<String>,
-    /// Amount of a token to be received.
+    /// ERC20 Token address or alias (same as `token_addr`).
+    token: Option<String>,
+    /// Amount of a token to be received (default amount of the token if missing).
+    #[serde(default)]
+    pub amount: u64,
+    /// IP address of the client (for the ledger).
+    #[serde(skip)]
+    pub client_ip: Option<String>,
+    /// Largest amount per request overriding the default (for API keys).
+    #[serde(skip)]
+    pub max_amount: Option<u64>,
+}
+
+impl Airdrop {
+    /// Resolves the token alias to the address and checks the amount is known.
+    pub fn resolve(&mut self) -> Result<(), Error> {
+        if let Some(name) = self.token.take().or_else(|| self.token_addr.take()) {
+            let token = config::web3_token(&name).ok_or(Error::UnknownToken(name))?;
+            self.token_addr = Some(token.address);
+        }
+        if self.tokens().iter().any(|t| self.amount_of(t) == 0) {
+            return Err(Error::BadJson("missing field `amount`".into()));
+        }
+        Ok(())
+    }
+
+    /// Returns the requested amount (the largest default amount if missing).
+    pub fn requested_amount(&self) -> u64 {
+        self.tokens()
+            .iter()
+            .map(|t| self.amount_of(t))
+            .max()
+            .unwrap_or(self.amount)
+    }
+
+    fn tokens(&self) -> Vec<config::Erc20Token> {
+        match self.token_addr {
+            Some(ref address) => config::web3_token(address).into_iter().collect(),
+            None => config::web3_tokens(),
+        }
+    }
+
+    fn amount_of(&self, token: &config::Erc20Token) -> u64 {
+        if self.amount > 0 {
+            self.amount
+        } else {
+            token.default_amount
+        }
+    }
+}
+
+/// Represents a single token transfer of the airdrop.
+struct TokenDrop {
+    /// ERC20 Token address as configured.
+    token: String,
+    address: ethereum::Address,
+    /// Amount in whole tokens.
     amount: u64,
+    /// Amount in fractions.
+    internal_amount: U256,
 }
 
-/// Processes the airdrop: sends needed transactions into Ethereum.
-pub async fn airdrop(id: &ReqId, params: Airdrop) -> Result<()> {
-    info!("{} Processing ERC20 {:?}...", id, params);
+/// Represents result of a single token transfer.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Transfer {
+    /// ERC20 Token address.
+    pub token: String,
+    /// Hash of the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<H256>,
+    /// Number of the block with the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<u64>,
+    pub status: Status,
+    /// Code of the error of a failed transfer (see `error::Error::code`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
+    /// Public message of the error of a failed transfer (details are in the log).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
 
-    let mut known_tokens = config::tokens();
+impl Transfer {
+    fn sent(token: &str, sent: &nonce::Sent) -> Self {
+        Transfer {
+            token: token.to_owned(),
+            hash: Some(sent.hash),
+            block: sent.block.map(|n| n.as_u64()),
+            status: if sent.confirmed {
+                Status::Confirmed
+            } else {
+                Status::Submitted
+            },
+            code: None,
+            error: None,
+        }
+    }
 
-    if let Some(ref token_addr) = params.token_addr {
-        if !known_tokens.contains(token_addr) {
-            return Err(eyre!("Requested unknown ERC20 '{}'", token_addr));
+    fn failed(token: &str, error: &eyre::Report) -> Self {
+        let (code, message) = Error::public(error);
+        Transfer {
+            token: token.to_owned(),
+            hash: None,
+            block: None,
+            status: Status::Failed,
+            code: Some(code),
+            error: Some(message),
         }
     }
+}
+
+/// Represents status of a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// The transaction is sent but not confirmed within the timeout.
+    Submitted,
+    /// The transaction has the required number of confirmations.
+    Confirmed,
+    /// The transaction is not sent or reverted.
+    Failed,
+}
 
-    if params.amount > config::web3_max_amount() {
-        return Err(eyre!(
-            "Requested value {} exceeds the limit {}",
-            params.amount,
-            config::web3_max_amount()
-        ));
+/// Represents result of the airdrop.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct Receipt {
+    pub transactions: Vec<Transfer>,
+}
+
+impl jobs::Outcome for Receipt {
+    fn status(&self) -> jobs::Status {
+        jobs::status_of(self.transactions.iter().map(|t| match t.status {
+            Status::Submitted => jobs::Status::Sent,
+            Status::Confirmed => jobs::Status::Confirmed,
+            Status::Failed => jobs::Status::Failed,
+        }))
     }
+}
+
+/// Represents balance of the admin in a token (in whole tokens).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Balance {
+    /// ERC20 Token address.
+    pub token: String,
+    /// Balance of the admin (unknown if the query has failed).
+    pub amount: Option<f64>,
+}
 
-    let admin_key: SecretKey = config::web3_private_key().parse()?;
-    let http = web3::transports::Http::new(&config::web3_rpc_url())?;
-    let web3 = web3::Web3::new(http);
+/// Processes the airdrop: sends needed transactions into Ethereum.
+pub async fn airdrop(id: &ReqId, mut params: Airdrop) -> Result<Receipt> {
+    info!("{} Processing ERC20 {:?}...", id, params);
 
-    if TOKENS.read().await.is_empty() {
-        init(id, web3.eth().clone(), &known_tokens).await?;
+    params.resolve()?;
+    let tokens = params.tokens();
+    if tokens.is_empty() {
+        return Err(Error::UnknownToken(params.token_addr.unwrap_or_default()).into());
+    }
+
+    for token in &tokens {
+        let amount = params.amount_of(token);
+        let max_amount = params.max_amount.unwrap_or(token.max_amount);
+        if amount > max_amount {
+            return Err(Error::AmountOverLimit(amount, max_amount).into());
+        }
     }
 
-    let recipient = ethereum::address_from_str(&params.wallet)?;
-    let amount = U256::from(params.amount);
+    let web3 = pool::web3()?;
 
-    if let Some(token_addr) = params.token_addr {
-        known_tokens = vec![token_addr]
+    init(id, web3.eth().clone(), &config::tokens()).await?;
+
+    let recipient = ethereum::address_from_str(&params.wallet)
+        .map_err(|_| Error::InvalidAddress(params.wallet.clone()))?;
+
+    let batch = config::web3_batch_contract();
+    let batch = if params.token_addr.is_none()
+        && tokens.len() > 1
+        && !batch.is_empty()
+        && !tokens
+            .iter()
+            .any(|t| config::web3_is_mint_token(&t.address))
+    {
+        Some(ethereum::address_from_str(&batch)?)
+    } else {
+        None
     };
 
-    for token in &known_tokens {
-        let factor = U256::from(multiplication_factor(token).await?);
-        let internal_amount = amount
+    let mut transfers = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let amount = params.amount_of(&token);
+        let factor = U256::from(multiplication_factor(&token.address).await?);
+        let internal_amount = U256::from(amount)
             .checked_mul(factor)
             .ok_or_else(|| eyre!("Overflow {} * {}", amount, factor))?;
-        transfer(
-            id,
-            web3.eth(),
-            ethereum::address_from_str(token)?,
-            token,
-            &admin_key,
-            recipient,
+        transfers.push(TokenDrop {
+            address: ethereum::address_from_str(&token.address)?,
+            token: token.address,
+            amount,
             internal_amount,
-        )
-        .await
-        .map_err(|e| {
-            error!("{} Failed transfer of token {}: {}", id, token, e);
-            e
-        })?;
+        });
     }
 
-    Ok(())
+    let required = transfers
+        .iter()
+        .filter(|t| !config::web3_is_mint_token(&t.token))
+        .map(|t| (t.address, t.internal_amount))
+        .collect::<Vec<_>>();
+    let admin = operators::web3(id, &required).await?;
+
+    let mut receipt = Receipt::default();
+
+    if let Some(batch) = batch {
+        let mut keys = Vec::with_capacity(transfers.len());
+        for t in &transfers {
+            keys.push(ledger::open(id, ledger_entry(&params, t)).await);
+        }
+
+        let r = transfer_batch(id, web3.eth(), batch, &transfers, &admin, recipient).await;
+
+        match r {
+            Ok(sent) => {
+                jobs::sent(id, format!("{:?}", sent.hash));
+                for (t, key) in transfers.iter().zip(keys) {
+                    ledger::close(
+                        id,
+                        key,
+                        ledger::Status::Success,
+                        Some(format!("{:?}", sent.hash)),
+                        None,
+                    )
+                    .await;
+                    metrics::airdropped(&t.token, t.amount as f64);
+                    receipt.transactions.push(Transfer::sent(&t.token, &sent));
+                }
+            }
+            Err(e) => {
+                error!("{} Failed batch transfer: {}", id, e);
+                for key in keys {
+                    ledger::close(id, key, ledger::Status::Failed, None, Some(e.to_string())).await;
+                }
+                return Err(e);
+            }
+        }
+        return Ok(receipt);
+    }
+
+    let mut first_error = None;
+    for t in &transfers {
+        let token = &t.token;
+        let key = ledger::open(id, ledger_entry(&params, t)).await;
+
+        let r = if config::web3_is_mint_token(token) {
+            mint(
+                id,
+                web3.eth(),
+                t.address,
+                token,
+                &admin,
+                recipient,
+                t.internal_amount,
+            )
+            .await
+        } else {
+            transfer(
+                id,
+                web3.eth(),
+                t.address,
+                token,
+                &admin,
+                recipient,
+                t.internal_amount,
+            )
+            .await
+        };
+
+        match r {
+            Ok(sent) => {
+                jobs::sent(id, format!("{:?}", sent.hash));
+                ledger::close(
+                    id,
+                    key,
+                    ledger::Status::Success,
+                    Some(format!("{:?}", sent.hash)),
+                    None,
+                )
+                .await;
+                metrics::airdropped(token, t.amount as f64);
+                receipt.transactions.push(Transfer::sent(token, &sent));
+            }
+            Err(e) => {
+                error!("{} Failed transfer of token {}: {}", id, token, e);
+                ledger::close(id, key, ledger::Status::Failed, None, Some(e.to_string())).await;
+                receipt.transactions.push(Transfer::failed(token, &e));
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    // The airdrop fails only if no token is transferred
+    match first_error {
+        Some(e)
+            if receipt
+                .transactions
+                .iter()
+                .all(|t| t.status == Status::Failed) =>
+        {
+            Err(e)
+        }
+        _ => Ok(receipt),
+    }
 }
 
-/// Initializes local cache of tokens properties.
+fn ledger_entry(params: &Airdrop, transfer: &TokenDrop) -> ledger::Entry {
+    ledger::Entry {
+        token: transfer.token.clone(),
+        wallet: params.wallet.clone(),
+        amount: transfer.amount,
+        in_fractions: false,
+        client_ip: params.client_ip.clone(),
+    }
+}
+
+/// Represents balances of an operator (admin) in all known tokens.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Balances {
+    /// Address of the operator.
+    pub operator: String,
+    pub tokens: Vec<Balance>,
+}
+
+/// Returns balances of all operators in all known tokens.
+/// The balances are queried at most once per `BALANCES_TTL`, concurrent callers share them.
+pub async fn balances(id: &ReqId) -> Result<Vec<Balances>> {
+    let mut cache = BALANCES.lock().await;
+    if let Some((time, balances)) = &*cache {
+        if time.elapsed() < BALANCES_TTL {
+            return Ok(balances.clone());
+        }
+    }
+
+    let balances = query_balances(id).await?;
+    *cache = Some((Instant::now(), balances.clone()));
+    Ok(balances)
+}
+
+/// Queries balances of all operators in all known tokens.
+async fn query_balances(id: &ReqId) -> Result<Vec<Balances>> {
+    let web3 = pool::web3()?;
+
+    let known_tokens = config::tokens();
+    if let Err(e) = init(id, web3.eth().clone(), &known_tokens).await {
+        warn!("{} Failed checking tokens: {:#}", id, e);
+    }
+
+    let mut balances = vec![];
+    for admin in pool::web3_operators().await? {
+        let admin = admin.address();
+        let mut tokens = Vec::with_capacity(known_tokens.len());
+        for token in &known_tokens {
+            let amount = match whole_balance_of(web3.eth(), token, admin).await {
+                Ok(amount) => Some(amount),
+                Err(e) => {
+                    warn!("{} Failed balance of {:?} in {}: {:#}", id, admin, token, e);
+                    metrics::rpc_error("web3");
+                    None
+                }
+            };
+            tokens.push(Balance {
+                token: token.clone(),
+                amount,
+            });
+        }
+        balances.push(Balances {
+            operator: format!("{:?}", admin),
+            tokens,
+        });
+    }
+
+    Ok(balances)
+}
+
+/// Returns balance of the owner in the known token (in whole tokens).
+async fn whole_balance_of<T: Transport>(
+    eth: Eth<T>,
+    token: &str,
+    owner: ethereum::Address,
+) -> Result<f64> {
+    let amount = balance_of(eth, ethereum::address_from_str(token)?, owner).await?;
+    let factor = multiplication_factor(token).await?;
+    Ok(amount.to_string().parse::<f64>()? / factor as f64)
+}
+
+/// Returns balance of the owner in the token (in fractions).
+pub async fn balance_of<T: Transport>(
+    eth: Eth<T>,
+    token: ethereum::Address,
+    owner: ethereum::Address,
+) -> Result<U256> {
+    let contract = Contract::from_json(eth, token, include_bytes!("../erc20/ERC20.abi"))?;
+    let amount = contract
+        .query("balanceOf", owner, None, Options::default(), None)
+        .await?;
+    Ok(amount)
+}
+
+/// Represents metadata of a token for the token list.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TokenInfo {
+    /// ERC20 Token address.
+    pub address: String,
+    /// Name of the token in requests (from `[[web3.token]]`).
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub alias: String,
+    /// Metadata of the token (unknown until the token is checked successfully).
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u32>,
+    /// Largest amount of the token per request (in whole tokens).
+    pub max_amount: u64,
+    /// Amount for requests without one (in whole tokens).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_amount: Option<u64>,
+    /// Remaining balance of all operators (in whole tokens; unknown if a query has failed).
+    pub balance: Option<f64>,
+    /// Flag of minting the token instead of transfer.
+    pub mint: bool,
+}
+
+/// Returns metadata and remaining balances of all known tokens.
+pub async fn list(id: &ReqId) -> Result<Vec<TokenInfo>> {
+    let balances = balances(id).await?;
+    let tokens = TOKENS.read().await;
+
+    let mut list = vec![];
+    for entry in config::web3_tokens() {
+        let address = entry.address;
+        let token = tokens.get(&address);
+        if token.is_none() {
+            warn!("{} Token info in cache not found: {}", id, address);
+        }
+        let balance = balances
+            .iter()
+            .flat_map(|b| &b.tokens)
+            .filter(|b| b.token == address)
+            .map(|b| b.amount)
+            .sum::<Option<f64>>();
+        list.push(TokenInfo {
+            alias: entry.alias,
+            name: token.map(|t| t.name.clone()),
+            symbol: token.map(|t| t.symbol.clone()),
+            decimals: token.map(|t| t.decimals),
+            max_amount: entry.max_amount,
+            default_amount: Some(entry.default_amount).filter(|a| *a > 0),
+            balance,
+            mint: config::web3_is_mint_token(&address),
+            address,
+        });
+    }
+
+    Ok(list)
+}
+
+/// Initializes local cache of tokens properties (for tokens missing in the cache).
 async fn init<T: Transport>(id: &ReqId, eth: Eth<T>, addresses: &[String]) -> Result<()> {
+    let missing = {
+        let tokens = TOKENS.read().await;
+        addresses
+            .iter()
+            .filter(|a| !tokens.contains_key(*a))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    if missing.is_empty() {
+        return Ok(());
+    }
+
     info!("{} Checking tokens...", id);
 
-    for token_address in addresses {
-        let a = ethereum::address_from_str(token_address)?;
-        TOKENS.write().await.insert(
-            token_address.to_string(),
-            Token::new(get_decimals(id, eth.clone(), a).await?),
-        );
+    // Tokens are checked independently: a failed token does not block the others
+    let mut first_error = None;
+    for token_address in &missing {
+        let token = match ethereum::address_from_str(token_address) {
+            Ok(a) => get_token(id, eth.clone(), a)
+                .await
+                .map_err(eyre::Report::from),
+            Err(e) => Err(e),
+        };
+        match token {
+            Ok(token) => {
+                TOKENS
+                    .write()
+                    .await
+                    .insert(token_address.to_string(), token);
+            }
+            Err(e) => {
+                error!("{} Failed checking token {}: {:#}", id, token_address, e);
+                first_error.get_or_insert(e);
+            }
+        }
     }
 
-    info!("{} All tokens are deployed and sane", id);
-    Ok(())
+    match first_error {
+        Some(e) => Err(e),
+        None => {
+            info!("{} All tokens are deployed and sane", id);
+            Ok(())
+        }
+    }
 }
 
 /// Creates and sends a transfer transaction.
@@ -104,16 +540,16 @@ async fn transfer<T: Transport>(
     eth: Eth<T>,
     token: ethereum::Address,
     token_name: &str,
-    admin_key: impl Key + std::fmt::Debug,
+    admin: &EthereumSigner,
     recipient: ethereum::Address,
     amount: U256,
-) -> web3::contract::Result<()> {
+) -> Result<nonce::Sent> {
     info!(
         "{} Transfer {} of token {} -> {}",
         id, amount, token_name, recipient
     );
-    let token =
-        Contract::from_json(eth, token, include_bytes!("../erc20/ERC20.abi")).map_err(|e| {
+    let token = Contract::from_json(eth.clone(), token, include_bytes!("../erc20/ERC20.abi"))
+        .map_err(|e| {
             error!("{} Failed reading ERC20.abi: {}", id, e);
             e
         })?;
@@ -122,33 +558,171 @@ async fn transfer<T: Transport>(
         "{} Sending transaction for transfer of token {}...",
         id, token_name
     );
-    let options = web3::contract::Options {
-        gas: Some(U256::from(10_000_000)),
-        ..Default::default()
-    };
-    token
-        .signed_call_with_confirmations(
+    let data = token
+        .abi()
+        .function("transfer")?
+        .encode_input(&(recipient, amount).into_tokens())?;
+    let estimate = token
+        .estimate_gas(
             "transfer",
             (recipient, amount),
-            options,
-            0, // confirmations
-            admin_key,
+            admin.address(),
+            Options::default(),
         )
-        .await
-        .map_err(|e| {
-            error!("{} Failed signed_call_with_confirmations: {}", id, e);
-            e
-        })?;
+        .await?;
+    let sent = nonce::send(
+        id,
+        eth,
+        admin,
+        token.address(),
+        data.into(),
+        gas::limit(estimate),
+    )
+    .await
+    .map_err(|e| {
+        error!("{} Failed sending transaction: {}", id, e);
+        e
+    })?;
+
+    info!("{} OK {:?} (confirmed: {})", id, sent.hash, sent.confirmed);
+    Ok(sent)
+}
+
+/// Creates and sends a transaction minting tokens to the recipient by the function of
+/// `web3.mint_abi`. Falls back to transfer if the operator is not authorized to mint
+/// (the call is reverted); other errors fail the mint.
+async fn mint<T: Transport>(
+    id: &ReqId,
+    eth: Eth<T>,
+    token: ethereum::Address,
+    token_name: &str,
+    admin: &EthereumSigner,
+    recipient: ethereum::Address,
+    amount: U256,
+) -> Result<nonce::Sent> {
+    info!(
+        "{} Mint {} of token {} -> {}",
+        id, amount, token_name, recipient
+    );
+    let function = config::web3_mint_abi_function()
+        .ok_or_else(|| eyre!("Mint function of web3.mint_abi is not loaded"))?;
+    let data = function.encode_input(&(recipient, amount).into_tokens())?;
+
+    let request = CallRequest {
+        from: Some(admin.address()),
+        to: Some(token),
+        data: Some(data.clone().into()),
+        ..Default::default()
+    };
+    let estimate = match eth.estimate_gas(request, None).await {
+        Ok(estimate) => estimate,
+        Err(e) if is_revert(&e) => {
+            warn!(
+                "{} Operator {:?} cannot mint token {} ({}), transferring instead",
+                id,
+                admin.address(),
+                token_name,
+                e
+            );
+            return transfer(id, eth, token, token_name, admin, recipient, amount).await;
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let sent = nonce::send(id, eth, admin, token, data.into(), gas::limit(estimate)).await?;
 
-    info!("{} OK", id);
+    info!("{} OK {:?} (confirmed: {})", id, sent.hash, sent.confirmed);
+    Ok(sent)
+}
+
+/// Checks the RPC error reports reverted execution of the call.
+fn is_revert(error: &web3::Error) -> bool {
+    matches!(error, web3::Error::Rpc(e) if e.message.to_lowercase().contains("revert"))
+}
+
+/// Creates and sends a transaction transferring all tokens through the batch contract.
+async fn transfer_batch<T: Transport>(
+    id: &ReqId,
+    eth: Eth<T>,
+    batch: ethereum::Address,
+    transfers: &[TokenDrop],
+    admin: &EthereumSigner,
+    recipient: ethereum::Address,
+) -> Result<nonce::Sent> {
+    info!(
+        "{} Batch transfer of {} tokens -> {}",
+        id,
+        transfers.len(),
+        recipient
+    );
+    for t in transfers {
+        check_allowance(
+            eth.clone(),
+            t.address,
+            &t.token,
+            admin,
+            batch,
+            t.internal_amount,
+        )
+        .await?;
+    }
+
+    let contract = Contract::from_json(eth.clone(), batch, include_bytes!("../erc20/Batch.abi"))?;
+    let tokens = transfers.iter().map(|t| t.address).collect::<Vec<_>>();
+    let amounts = transfers
+        .iter()
+        .map(|t| t.internal_amount)
+        .collect::<Vec<_>>();
+    let data = contract
+        .abi()
+        .function("transferBatch")?
+        .encode_input(&(tokens.clone(), recipient, amounts.clone()).into_tokens())?;
+    let estimate = contract
+        .estimate_gas(
+            "transferBatch",
+            (tokens, recipient, amounts),
+            admin.address(),
+            Options::default(),
+        )
+        .await?;
+    let sent = nonce::send(id, eth, admin, batch, data.into(), gas::limit(estimate)).await?;
+
+    info!("{} OK {:?} (confirmed: {})", id, sent.hash, sent.confirmed);
+    Ok(sent)
+}
+
+/// Checks the spender is allowed to transfer the amount of the token from the operator.
+/// The allowance is provisioned by the owner of the operator, the faucet never approves.
+async fn check_allowance<T: Transport>(
+    eth: Eth<T>,
+    token: ethereum::Address,
+    token_name: &str,
+    admin: &EthereumSigner,
+    spender: ethereum::Address,
+    amount: U256,
+) -> Result<()> {
+    let contract = Contract::from_json(eth, token, include_bytes!("../erc20/ERC20.abi"))?;
+    let allowance: U256 = contract
+        .query(
+            "allowance",
+            (admin.address(), spender),
+            None,
+            Options::default(),
+            None,
+        )
+        .await?;
+    if allowance < amount {
+        return Err(Error::InsufficientAllowance(token_name.into()).into());
+    }
     Ok(())
 }
 
-async fn get_decimals<T: Transport>(
+/// Queries properties of the token: decimals (required), name and symbol (optional).
+async fn get_token<T: Transport>(
     id: &ReqId,
     eth: Eth<T>,
     token_address: ethereum::Address,
-) -> web3::contract::Result<u32> {
+) -> web3::contract::Result<Token> {
     let token = Contract::from_json(eth, token_address, include_bytes!("../erc20/ERC20.abi"))
         .map_err(|e| {
             error!("{} Failed reading ERC20.abi: {}", id, e);
@@ -158,12 +732,31 @@ async fn get_decimals<T: Transport>(
     let decimals = token
         .query("decimals", (), None, Options::default(), None)
         .await?;
+    let name = query_text(id, &token, "name").await;
+    let symbol = query_text(id, &token, "symbol").await;
     debug!(
-        "{} ERC20 token {} has decimals {}",
-        id, token_address, decimals
+        "{} ERC20 token {} ({}) has decimals {}",
+        id, token_address, symbol, decimals
     );
 
-    Ok(decimals)
+    Ok(Token::new(decimals, name, symbol))
+}
+
+/// Queries an optional string property of the token (empty if not supported).
+async fn query_text<T: Transport>(id: &ReqId, token: &Contract<T>, function: &str) -> String {
+    token
+        .query(function, (), None, Options::default(), None)
+        .await
+        .unwrap_or_else(|e| {
+            warn!(
+                "{} ERC20 token {:?} has no valid {}: {}",
+                id,
+                token.address(),
+                function,
+                e
+            );
+            String::default()
+        })
 }
 
 /// Returns multiplication factor to convert whole token value to fractions.
@@ -183,15 +776,18 @@ async fn multiplication_factor(token_address: &str) -> Result<u64> {
 }
 
 use derive_new::new;
-use futures_locks::RwLock;
+use futures_locks::{Mutex, RwLock};
 
 #[derive(new, Debug, Default, Clone)]
 struct Token {
     decimals: u32,
+    name: String,
+    symbol: String,
 }
 
 type Tokens = std::collections::HashMap<String, Token>;
 
 lazy_static::lazy_static! {
     static ref TOKENS: RwLock<Tokens> = RwLock::new(Tokens::default());
+    static ref BALANCES: Mutex<Option<(Instant, Vec<Balances>)>> = Mutex::new(None);
 }
@@ -0,0 +1,210 @@
+//! Faucet API errors module.
+
+use actix_web::http::StatusCode;
+use serde::Serialize;
+
+use crate::id::ReqId;
+use crate::{auth, captcha, limits, nonce};
+
+/// Represents errors reported to clients.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid request body: {0}")]
+    BadBody(String),
+
+    #[error("Invalid JSON: {0}")]
+    BadJson(String),
+
+    #[error("Invalid address '{0}'")]
+    InvalidAddress(String),
+
+    #[error("Requested unknown token '{0}'")]
+    UnknownToken(String),
+
+    #[error("Requested value {0} exceeds the limit {1}")]
+    AmountOverLimit(u64, u64),
+
+    #[error("Insufficient balance of the faucet")]
+    InsufficientBalance,
+
+    #[error("Insufficient allowance of the batch contract for token '{0}'")]
+    InsufficientAllowance(String),
+
+    #[error("{0}")]
+    RateLimited(#[from] limits::Error),
+
+    #[error("{0}")]
+    Captcha(#[from] captcha::Error),
+
+    #[error("{0}")]
+    Unauthorized(#[from] auth::Error),
+
+    #[error("Unknown job '{0}'")]
+    UnknownJob(String),
+
+    #[error("Transaction is reverted")]
+    TransactionReverted,
+
+    #[error("Transaction is not mined in time")]
+    TransactionStuck,
+
+    /// Failed request to the upstream RPC (`solana` or `web3`).
+    #[error("Upstream RPC request failed")]
+    UpstreamRpc(&'static str),
+
+    #[error("Internal server error")]
+    Internal,
+}
+
+/// Represents JSON body of an error response.
+#[derive(Debug, Clone, Serialize)]
+pub struct Body {
+    pub code: &'static str,
+    pub message: String,
+    pub req_id: String,
+}
+
+impl Error {
+    /// Returns stable code of the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::BadBody(_) => "bad_body",
+            Error::BadJson(_) => "bad_json",
+            Error::InvalidAddress(_) => "invalid_address",
+            Error::UnknownToken(_) => "unknown_token",
+            Error::AmountOverLimit(_, _) => "amount_over_limit",
+            Error::InsufficientBalance => "insufficient_balance",
+            Error::InsufficientAllowance(_) => "insufficient_allowance",
+            Error::RateLimited(_) => "rate_limited",
+            Error::Captcha(captcha::Error::Unavailable(_)) => "captcha_unavailable",
+            Error::Captcha(_) => "captcha_failed",
+            Error::Unauthorized(auth::Error::Forbidden(_, _)) => "forbidden",
+            Error::Unauthorized(_) => "unauthorized",
+            Error::UnknownJob(_) => "unknown_job",
+            Error::TransactionReverted => "transaction_reverted",
+            Error::TransactionStuck => "transaction_stuck",
+            Error::UpstreamRpc(_) => "upstream_rpc",
+            Error::Internal => "internal",
+        }
+    }
+
+    /// Returns HTTP status of the error.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Error::BadBody(_)
+            | Error::BadJson(_)
+            | Error::InvalidAddress(_)
+            | Error::UnknownToken(_)
+            | Error::AmountOverLimit(_, _) => StatusCode::BAD_REQUEST,
+            Error::InsufficientBalance | Error::InsufficientAllowance(_) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            Error::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Error::Captcha(captcha::Error::Unavailable(_)) => StatusCode::SERVICE_UNAVAILABLE,
+            Error::Captcha(_) => StatusCode::FORBIDDEN,
+            Error::Unauthorized(auth::Error::Forbidden(_, _)) => StatusCode::FORBIDDEN,
+            Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Error::UnknownJob(_) => StatusCode::NOT_FOUND,
+            Error::TransactionReverted => StatusCode::BAD_GATEWAY,
+            Error::TransactionStuck => StatusCode::SERVICE_UNAVAILABLE,
+            Error::UpstreamRpc(_) => StatusCode::BAD_GATEWAY,
+            Error::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Returns JSON body of the error.
+    pub fn body(&self, id: &ReqId) -> Body {
+        Body {
+            code: self.code(),
+            message: self.to_string(),
+            req_id: id.as_str().to_owned(),
+        }
+    }
+
+    /// Classifies an error report: finds a typed error in the chain of causes
+    /// or hides the details behind upstream/internal error.
+    pub fn from_report(report: eyre::Report) -> Self {
+        for cause in report.chain() {
+            if cause.is::<Error>() {
+                return match report.downcast::<Error>() {
+                    Ok(e) => e,
+                    Err(_) => Error::Internal,
+                };
+            }
+            if let Some(e) = classify(cause) {
+                return e;
+            }
+        }
+        Error::Internal
+    }
+
+    /// Returns code and public message of an error report (same as `from_report`),
+    /// leaving the report with its details for the log.
+    pub fn public(report: &eyre::Report) -> (&'static str, String) {
+        for cause in report.chain() {
+            if let Some(e) = cause.downcast_ref::<Error>() {
+                return (e.code(), e.to_string());
+            }
+            if let Some(e) = classify(cause) {
+                return (e.code(), e.to_string());
+            }
+        }
+        (Error::Internal.code(), Error::Internal.to_string())
+    }
+}
+
+/// Classifies a failure of the upstream or of a transaction.
+fn classify(cause: &(dyn std::error::Error + 'static)) -> Option<Error> {
+    if let Some(e) = cause.downcast_ref::<nonce::Error>() {
+        return Some(match e {
+            nonce::Error::Reverted(_) => Error::TransactionReverted,
+            nonce::Error::Stuck(..) => Error::TransactionStuck,
+        });
+    }
+    if cause.is::<solana_client::client_error::ClientError>() {
+        return Some(Error::UpstreamRpc("solana"));
+    }
+    if cause.is::<web3::Error>() {
+        return Some(Error::UpstreamRpc("web3"));
+    }
+    None
+}
+
+#[test]
+fn test_from_report() {
+    use eyre::WrapErr as _;
+
+    let e = Error::from_report(Error::UnknownToken("A".into()).into());
+    assert_eq!(e.code(), "unknown_token");
+
+    let e = Error::from_report(Error::InsufficientAllowance("A".into()).into());
+    assert_eq!(e.code(), "insufficient_allowance");
+    assert_eq!(e.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let r: eyre::Result<()> = Err(web3::Error::Unreachable.into());
+    let e = Error::from_report(r.wrap_err("transfer").unwrap_err());
+    assert_eq!(e.code(), "upstream_rpc");
+    assert!(matches!(e, Error::UpstreamRpc("web3")));
+    assert_eq!(e.status(), StatusCode::BAD_GATEWAY);
+
+    let r: eyre::Result<()> = Err(nonce::Error::Reverted(Default::default()).into());
+    let e = Error::from_report(r.wrap_err("transfer").unwrap_err());
+    assert_eq!(e.code(), "transaction_reverted");
+    assert_eq!(e.status(), StatusCode::BAD_GATEWAY);
+    let r = nonce::Error::Stuck(Default::default(), 7.into(), vec![]);
+    let e = Error::from_report(r.into());
+    assert_eq!(e.code(), "transaction_stuck");
+    assert_eq!(e.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let r = eyre::Report::from(web3::Error::Unreachable).wrap_err("http://secret:8545");
+    assert_eq!(
+        Error::public(&r),
+        ("upstream_rpc", "Upstream RPC request failed".into())
+    );
+    let r = eyre::Report::from(Error::UnknownToken("A".into())).wrap_err("context");
+    assert_eq!(Error::public(&r).0, "unknown_token");
+
+    let e = Error::from_report(eyre::eyre!("Something went wrong"));
+    assert_eq!(e.code(), "internal");
+    assert_eq!(e.to_string(), "Internal server error");
+}
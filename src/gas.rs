@@ -0,0 +1,124 @@
+//! Faucet gas strategy module.
+
+use web3::api::Eth;
+use web3::types::{BlockId, BlockNumber, TransactionRequest, U256, U64};
+use web3::Transport;
+
+use crate::config;
+
+/// Transaction type of EIP-1559.
+const EIP1559_TX_TYPE: u64 = 2;
+
+/// Represents fees of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fees {
+    Legacy {
+        gas_price: U256,
+    },
+    Eip1559 {
+        max_fee: U256,
+        max_priority_fee: U256,
+    },
+}
+
+/// Returns fees of a new transaction: legacy gas price of the network capped by
+/// `web3.max_gas_price` or EIP-1559 fees when `web3.max_fee_per_gas` is set.
+pub async fn fees<T: Transport>(eth: &Eth<T>) -> web3::Result<Fees> {
+    let max_fee_cap = gwei(config::web3_max_fee_per_gas());
+    if max_fee_cap.is_zero() {
+        let gas_price = eth.gas_price().await?;
+        return Ok(Fees::Legacy {
+            gas_price: cap(gas_price, gwei(config::web3_max_gas_price())),
+        });
+    }
+
+    let max_priority_fee = gwei(config::web3_max_priority_fee_per_gas());
+    let base_fee = eth
+        .block(BlockId::Number(BlockNumber::Latest))
+        .await?
+        .and_then(|b| b.base_fee_per_gas);
+    let max_fee = match base_fee {
+        Some(base_fee) => cap(base_fee * 2 + max_priority_fee, max_fee_cap),
+        None => max_fee_cap,
+    };
+    Ok(Fees::Eip1559 {
+        max_fee,
+        max_priority_fee,
+    })
+}
+
+impl Fees {
+    /// Returns fees raised by 20% for a replacement (`None` if they are at the caps).
+    pub fn bump(self) -> Option<Fees> {
+        let bumped = match self {
+            Fees::Legacy { gas_price } => Fees::Legacy {
+                gas_price: cap(raise(gas_price), gwei(config::web3_max_gas_price())),
+            },
+            Fees::Eip1559 {
+                max_fee,
+                max_priority_fee,
+            } => {
+                let max_fee = cap(raise(max_fee), gwei(config::web3_max_fee_per_gas()));
+                Fees::Eip1559 {
+                    max_fee,
+                    max_priority_fee: raise(max_priority_fee).min(max_fee),
+                }
+            }
+        };
+        (bumped != self).then_some(bumped)
+    }
+
+    /// Sets the fees of the transaction.
+    pub fn apply(self, tx: &mut TransactionRequest) {
+        match self {
+            Fees::Legacy { gas_price } => tx.gas_price = Some(gas_price),
+            Fees::Eip1559 {
+                max_fee,
+                max_priority_fee,
+            } => {
+                tx.transaction_type = Some(U64::from(EIP1559_TX_TYPE));
+                tx.max_fee_per_gas = Some(max_fee);
+                tx.max_priority_fee_per_gas = Some(max_priority_fee);
+            }
+        }
+    }
+}
+
+/// Applies `web3.gas_multiplier` to the estimated gas.
+pub fn limit(estimate: U256) -> U256 {
+    let percent = (config::web3_gas_multiplier() * 100.0).ceil() as u64;
+    estimate.saturating_mul(U256::from(percent)) / 100
+}
+
+fn gwei(value: u64) -> U256 {
+    U256::from(value) * U256::exp10(9)
+}
+
+fn raise(value: U256) -> U256 {
+    value.saturating_mul(U256::from(12)) / 10
+}
+
+/// Limits the value by the cap (zero cap means no limit).
+fn cap(value: U256, cap: U256) -> U256 {
+    if cap.is_zero() {
+        value
+    } else {
+        value.min(cap)
+    }
+}
+
+#[test]
+fn test_bump() {
+    let fees = Fees::Legacy {
+        gas_price: gwei(100),
+    };
+    assert_eq!(
+        fees.bump(),
+        Some(Fees::Legacy {
+            gas_price: gwei(120)
+        })
+    );
+    assert_eq!(cap(raise(gwei(100)), gwei(110)), gwei(110));
+    assert_eq!(cap(gwei(100), U256::zero()), gwei(100));
+    assert_eq!(limit(U256::from(100_000)), U256::from(120_000));
+}
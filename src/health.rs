@@ -0,0 +1,127 @@
+//! Faucet health checks module.
+
+use std::collections::BTreeMap;
+
+use eyre::{eyre, Result};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::id::ReqId;
+use crate::{config, erc20_tokens, neon_token, operators, pool};
+
+/// Represents result of a single check.
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl Check {
+    fn new<T: Serialize>(r: Result<T>) -> Self {
+        match r {
+            Ok(details) => Check {
+                ok: true,
+                error: None,
+                details: serde_json::to_value(details).ok().filter(|v| !v.is_null()),
+            },
+            Err(e) => Check {
+                ok: false,
+                error: Some(format!("{:#}", e)),
+                details: None,
+            },
+        }
+    }
+}
+
+/// Represents readiness of the faucet with breakdown by checks.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub ready: bool,
+    pub checks: BTreeMap<&'static str, Check>,
+}
+
+/// Checks upstreams and balances needed to serve airdrops.
+pub async fn ready(id: &ReqId) -> Report {
+    let mut checks = BTreeMap::new();
+
+    if config::solana_enabled() {
+        checks.insert("solana_rpc", Check::new(solana_rpc().await));
+        let params = Check::new(neon_params().await);
+        let token_account = if params.ok {
+            Check::new(neon_token::check_token_account(id).await)
+        } else {
+            Check::new::<()>(Err(eyre!("NEON parameters are not loaded")))
+        };
+        checks.insert("neon_params", params);
+        checks.insert("token_account", token_account);
+        checks.insert(
+            "solana_operators",
+            Check::new(operators::solana_balances().await),
+        );
+    }
+
+    if config::web3_enabled() {
+        checks.insert("web3_rpc", Check::new(web3_rpc().await));
+        checks.insert(
+            "web3_operators",
+            Check::new(operators::web3_balances().await),
+        );
+        checks.insert("erc20_balances", Check::new(erc20_balances(id).await));
+    }
+
+    let ready = checks.values().all(|c| c.ok);
+    if !ready {
+        for (name, check) in checks.iter().filter(|(_, c)| !c.ok) {
+            warn!("{} Check '{}' failed: {:?}", id, name, check.error);
+        }
+    }
+
+    Report { ready, checks }
+}
+
+/// Checks the Solana RPC is reachable.
+async fn solana_rpc() -> Result<String> {
+    let version = tokio::task::spawn_blocking(|| -> Result<_> {
+        let client = pool::solana_client();
+        Ok(client.get_version()?)
+    })
+    .await??;
+
+    Ok(version.solana_core)
+}
+
+/// Loads NEON parameters from the EVM Loader if not yet loaded.
+async fn neon_params() -> Result<()> {
+    if config::solana_account_seed_version() == 0 {
+        config::load_neon_params().await?;
+    }
+    Ok(())
+}
+
+/// Checks the Web3 RPC is reachable.
+async fn web3_rpc() -> Result<u64> {
+    let web3 = pool::web3()?;
+    Ok(web3.eth().block_number().await?.as_u64())
+}
+
+/// Checks some operator has non-zero balance of every token.
+async fn erc20_balances(id: &ReqId) -> Result<Vec<erc20_tokens::Balances>> {
+    let balances = erc20_tokens::balances(id).await?;
+    // Minted tokens need no balance
+    for token in config::tokens()
+        .into_iter()
+        .filter(|t| !config::web3_is_mint_token(t))
+    {
+        let funded = balances
+            .iter()
+            .flat_map(|b| &b.tokens)
+            .any(|b| b.token == token && b.amount.unwrap_or_default() > 0.0);
+        if !funded {
+            return Err(eyre!("No operator has balance of token {}", token));
+        }
+    }
+    Ok(balances)
+}
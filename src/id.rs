@@ -20,6 +20,7 @@ pub fn generate() -> ReqId {
     let digest = md5::compute(since.as_nanos().to_string());
     ReqId {
         id: format!("{:x}", digest)[..7].to_string(),
+        job: None,
     }
 }
 
@@ -27,12 +28,26 @@ pub fn generate() -> ReqId {
 #[derive(Default, Clone)]
 pub struct ReqId {
     id: String,
+    job: Option<String>,
 }
 
 impl ReqId {
     pub fn as_str(&self) -> &str {
         self.id.as_ref()
     }
+
+    /// Returns id of the airdrop job handling the request, if any.
+    pub fn job(&self) -> Option<&str> {
+        self.job.as_deref()
+    }
+
+    /// Returns the same context bound to the airdrop job.
+    pub fn with_job(&self, job: String) -> ReqId {
+        ReqId {
+            id: self.id.clone(),
+            job: Some(job),
+        }
+    }
 }
 
 use std::fmt;
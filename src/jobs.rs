@@ -0,0 +1,265 @@
+//! Faucet airdrop jobs module.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::info;
+
+use crate::error::{Body, Error};
+use crate::id::ReqId;
+
+/// Time to keep a finished job available for status requests.
+const JOB_TTL: Duration = Duration::from_secs(3600);
+
+/// Time after which an unfinished job is considered failed.
+const UNFINISHED_TTL: Duration = Duration::from_secs(4 * 3600);
+
+/// Upper bound of kept jobs; the least recently updated jobs are dropped above it.
+const MAX_JOBS: usize = 100_000;
+
+lazy_static::lazy_static! {
+    static ref JOBS: Mutex<HashMap<String, Job>> = Mutex::new(HashMap::new());
+}
+
+/// Represents state of an airdrop job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Queued,
+    Sent,
+    Confirmed,
+    /// Some of the transfers have failed (see the receipt).
+    Partial,
+    Failed,
+}
+
+/// Represents result of an airdrop kept by its job.
+pub trait Outcome: Serialize {
+    /// Returns state of the job finished with the result.
+    fn status(&self) -> Status;
+}
+
+/// Combines states of single transfers (`Sent`, `Confirmed` or `Failed`) into the job state.
+pub fn status_of(transfers: impl IntoIterator<Item = Status>) -> Status {
+    let (mut failed, mut sent, mut total) = (0, 0, 0);
+    for status in transfers {
+        total += 1;
+        match status {
+            Status::Failed => failed += 1,
+            Status::Confirmed => {}
+            _ => sent += 1,
+        }
+    }
+    match (failed, sent) {
+        (0, 0) => Status::Confirmed,
+        (0, _) => Status::Sent,
+        (f, _) if f == total => Status::Failed,
+        _ => Status::Partial,
+    }
+}
+
+/// Represents an airdrop job.
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    /// Job id (random 128-bit hex string).
+    pub id: String,
+    /// Current state.
+    pub status: Status,
+    /// Signatures (Solana) or hashes (Ethereum) of sent transactions.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<String>,
+    /// Reason of failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<Body>,
+    /// Result of the finished airdrop (same as the response of a synchronous request).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<serde_json::Value>,
+    #[serde(skip)]
+    req_id: String,
+    #[serde(skip)]
+    updated: Instant,
+}
+
+/// Registers a new job in the queued state.
+/// Returns the request context bound to the job.
+pub fn enqueue(id: &ReqId) -> ReqId {
+    let now = Instant::now();
+    let job_id = format!("{:032x}", rand::random::<u128>());
+    let mut jobs = JOBS.lock().unwrap();
+    evict(&mut jobs, now);
+    jobs.insert(
+        job_id.clone(),
+        Job {
+            id: job_id.clone(),
+            status: Status::Queued,
+            transactions: Vec::new(),
+            error: None,
+            receipt: None,
+            req_id: id.as_str().to_owned(),
+            updated: now,
+        },
+    );
+    info!("{} Job {} is queued (total {})", id, job_id, jobs.len());
+    id.with_job(job_id)
+}
+
+/// Marks the job as sent with a transaction. Does nothing if the request has no job.
+pub fn sent(id: &ReqId, tx: String) {
+    update(id, |job| {
+        job.status = Status::Sent;
+        job.transactions.push(tx);
+    });
+}
+
+/// Marks the job as finished with the result of the airdrop.
+pub fn finished<R: Outcome>(id: &ReqId, receipt: &R) {
+    let status = receipt.status();
+    let receipt = serde_json::to_value(receipt).ok();
+    update(id, |job| {
+        job.status = status;
+        job.receipt = receipt;
+    });
+}
+
+/// Marks the job as failed.
+pub fn failed(id: &ReqId, error: Body) {
+    update(id, |job| {
+        job.status = Status::Failed;
+        job.error = Some(error);
+    });
+}
+
+/// Returns the job by id.
+pub fn get(id: &str) -> Option<Job> {
+    JOBS.lock().unwrap().get(id).cloned()
+}
+
+fn update(id: &ReqId, f: impl FnOnce(&mut Job)) {
+    let job_id = match id.job() {
+        Some(job_id) => job_id,
+        None => return,
+    };
+    if let Some(job) = JOBS.lock().unwrap().get_mut(job_id) {
+        f(job);
+        job.updated = Instant::now();
+        info!("{} Job {} is {:?}", id, job_id, job.status);
+    }
+}
+
+/// Fails unfinished jobs older than `UNFINISHED_TTL`, drops finished jobs older than
+/// `JOB_TTL` and the least recently updated jobs above `MAX_JOBS`.
+fn evict(jobs: &mut HashMap<String, Job>, now: Instant) {
+    for job in jobs.values_mut() {
+        let unfinished = matches!(job.status, Status::Queued | Status::Sent);
+        if unfinished && now.saturating_duration_since(job.updated) >= UNFINISHED_TTL {
+            job.status = Status::Failed;
+            job.error = Some(Body {
+                code: Error::Internal.code(),
+                message: "Job has not finished in time".into(),
+                req_id: job.req_id.clone(),
+            });
+            job.updated = now;
+        }
+    }
+    jobs.retain(|_, job| {
+        matches!(job.status, Status::Queued | Status::Sent)
+            || now.saturating_duration_since(job.updated) < JOB_TTL
+    });
+    if jobs.len() >= MAX_JOBS {
+        let mut updated = jobs.values().map(|job| job.updated).collect::<Vec<_>>();
+        updated.sort_unstable();
+        let oldest = updated[jobs.len() - MAX_JOBS];
+        jobs.retain(|_, job| job.updated > oldest);
+    }
+}
+
+#[test]
+fn test_job_status() {
+    let id = enqueue(&crate::id::generate());
+    let job_id = id.job().unwrap().to_owned();
+    assert_eq!(job_id.len(), 32);
+    assert_eq!(get(&job_id).unwrap().status, Status::Queued);
+
+    sent(&id, "tx1".into());
+    let job = get(&job_id).unwrap();
+    assert_eq!(job.status, Status::Sent);
+    assert_eq!(job.transactions, vec!["tx1"]);
+    let receipt = crate::solana::Receipt {
+        signature: "tx1".into(),
+        slot: Some(5),
+    };
+    finished(&id, &receipt);
+    let job = get(&job_id).unwrap();
+    assert_eq!(job.status, Status::Confirmed);
+    assert_eq!(job.receipt.unwrap()["slot"], 5);
+
+    // Jobs of the same request are distinct
+    let other = enqueue(&id);
+    assert_ne!(other.job(), id.job());
+    let error = Body {
+        code: "upstream_rpc",
+        message: "boom".into(),
+        req_id: id.as_str().into(),
+    };
+    failed(&other, error);
+    assert_eq!(get(other.job().unwrap()).unwrap().status, Status::Failed);
+    assert_eq!(get(&job_id).unwrap().status, Status::Confirmed);
+
+    // Request without a job changes nothing
+    finished(&crate::id::generate(), &receipt);
+    assert_eq!(get(other.job().unwrap()).unwrap().status, Status::Failed);
+}
+
+#[test]
+fn test_evict() {
+    let now = Instant::now();
+    let job = |status| Job {
+        id: String::new(),
+        status,
+        transactions: Vec::new(),
+        error: None,
+        receipt: None,
+        req_id: String::new(),
+        updated: now,
+    };
+    let mut jobs = HashMap::new();
+    jobs.insert("queued".to_string(), job(Status::Queued));
+    jobs.insert("sent".to_string(), job(Status::Sent));
+    jobs.insert("confirmed".to_string(), job(Status::Confirmed));
+    jobs.insert("failed".to_string(), job(Status::Failed));
+
+    evict(&mut jobs, now + JOB_TTL - Duration::from_secs(1));
+    assert_eq!(jobs.len(), 4);
+    evict(&mut jobs, now + JOB_TTL);
+    let mut left = jobs.keys().cloned().collect::<Vec<_>>();
+    left.sort();
+    assert_eq!(left, vec!["queued", "sent"]);
+
+    evict(&mut jobs, now + UNFINISHED_TTL);
+    assert_eq!(jobs["queued"].status, Status::Failed);
+    assert_eq!(jobs["sent"].status, Status::Failed);
+    evict(&mut jobs, now + UNFINISHED_TTL + JOB_TTL);
+    assert!(jobs.is_empty());
+
+    for i in 0..MAX_JOBS {
+        let mut job = job(Status::Sent);
+        job.updated = now + Duration::from_millis(i as u64);
+        jobs.insert(i.to_string(), job);
+    }
+    evict(&mut jobs, now);
+    assert_eq!(jobs.len(), MAX_JOBS - 1);
+    assert!(!jobs.contains_key("0"));
+}
+
+#[test]
+fn test_status_of() {
+    use Status::*;
+    assert_eq!(status_of([]), Confirmed);
+    assert_eq!(status_of([Confirmed, Confirmed]), Confirmed);
+    assert_eq!(status_of([Confirmed, Sent]), Sent);
+    assert_eq!(status_of([Sent, Failed]), Partial);
+    assert_eq!(status_of([Confirmed, Failed]), Partial);
+    assert_eq!(status_of([Failed, Failed]), Failed);
+}
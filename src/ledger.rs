@@ -0,0 +1,233 @@
+//! Faucet airdrop ledger module.
+
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection};
+use tracing::{error, info};
+
+use crate::{config, id, id::ReqId};
+
+lazy_static::lazy_static! {
+    static ref DB: Mutex<Option<Connection>> = Mutex::new(None);
+}
+
+/// Represents the ledger errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Storage error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+/// Represents the ledger result type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Represents state of an airdrop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pending,
+    Success,
+    Failed,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Pending => "pending",
+            Status::Success => "success",
+            Status::Failed => "failed",
+        }
+    }
+}
+
+/// Represents a single airdrop record.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// Token name or address.
+    pub token: String,
+    /// Recipient address.
+    pub wallet: String,
+    /// Requested amount.
+    pub amount: u64,
+    /// Amount is in fractions (true) or in whole tokens (false).
+    pub in_fractions: bool,
+    /// IP address of the client.
+    pub client_ip: Option<String>,
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS airdrops (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    req_id       TEXT    NOT NULL,
+    wallet       TEXT    NOT NULL,
+    token        TEXT    NOT NULL,
+    amount       INTEGER NOT NULL,
+    in_fractions INTEGER NOT NULL,
+    tx           TEXT,
+    status       TEXT    NOT NULL,
+    error        TEXT,
+    client_ip    TEXT,
+    created_at   TEXT    NOT NULL,
+    updated_at   TEXT    NOT NULL
+);
+CREATE INDEX IF NOT EXISTS airdrops_wallet ON airdrops (wallet, created_at);
+CREATE INDEX IF NOT EXISTS airdrops_req_id ON airdrops (req_id);
+";
+
+/// Opens (or creates) the database if the storage is enabled.
+pub fn init() -> Result<()> {
+    if !config::storage_enabled() {
+        return Ok(());
+    }
+
+    let path = config::storage_path();
+    info!("{} Opening ledger {:?}...", id::default(), path);
+    let connection = Connection::open(&path)?;
+    connection.execute_batch(SCHEMA)?;
+    *DB.lock().unwrap() = Some(connection);
+    Ok(())
+}
+
+/// Records a new airdrop in pending state. Returns the record key, if any.
+pub async fn open(id: &ReqId, entry: Entry) -> Option<i64> {
+    let req_id = id.as_str().to_owned();
+    let r = tokio::task::spawn_blocking(move || -> Result<Option<i64>> {
+        let db = DB.lock().unwrap();
+        let connection = match db.as_ref() {
+            Some(connection) => connection,
+            None => return Ok(None),
+        };
+        let now = timestamp();
+        connection.execute(
+            "INSERT INTO airdrops \
+             (req_id, wallet, token, amount, in_fractions, status, client_ip, created_at, updated_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+            params![
+                req_id,
+                entry.wallet.to_lowercase(),
+                entry.token,
+                entry.amount,
+                entry.in_fractions,
+                Status::Pending.as_str(),
+                entry.client_ip,
+                now,
+            ],
+        )?;
+        Ok(Some(connection.last_insert_rowid()))
+    })
+    .await;
+
+    match r {
+        Ok(Ok(key)) => key,
+        Ok(Err(err)) => {
+            error!("{} Failed ledger::open: {}", id, err);
+            None
+        }
+        Err(err) => {
+            error!("{} Failed ledger::open: {}", id, err);
+            None
+        }
+    }
+}
+
+/// Updates state of the airdrop record with transaction signature or error.
+pub async fn close(
+    id: &ReqId,
+    key: Option<i64>,
+    status: Status,
+    tx: Option<String>,
+    error: Option<String>,
+) {
+    let key = match key {
+        Some(key) => key,
+        None => return,
+    };
+
+    let r = tokio::task::spawn_blocking(move || -> Result<()> {
+        let db = DB.lock().unwrap();
+        if let Some(connection) = db.as_ref() {
+            connection.execute(
+                "UPDATE airdrops SET status = ?1, tx = ?2, error = ?3, updated_at = ?4 WHERE id = ?5",
+                params![status.as_str(), tx, error, timestamp(), key],
+            )?;
+        }
+        Ok(())
+    })
+    .await;
+
+    match r {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => error!("{} Failed ledger::close: {}", id, err),
+        Err(err) => error!("{} Failed ledger::close: {}", id, err),
+    }
+}
+
+/// Returns current time in RFC 3339 format.
+fn timestamp() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+#[test]
+fn test_open_close() {
+    let connection = Connection::open_in_memory().unwrap();
+    connection.execute_batch(SCHEMA).unwrap();
+    *DB.lock().unwrap() = Some(connection);
+
+    let id = id::generate();
+    let entry = Entry {
+        token: "NEON".into(),
+        wallet: "0xABCD".into(),
+        amount: 10,
+        in_fractions: false,
+        client_ip: Some("127.0.0.1".into()),
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let key = open(&id, entry.clone()).await;
+        assert!(key.is_some());
+        close(&id, key, Status::Success, Some("sig".into()), None).await;
+        let key = open(&id, entry).await;
+        close(&id, key, Status::Failed, None, Some("boom".into())).await;
+    });
+
+    let db = DB.lock().unwrap();
+    let mut statement = db
+        .as_ref()
+        .unwrap()
+        .prepare("SELECT req_id, wallet, status, tx, error FROM airdrops ORDER BY id")
+        .unwrap();
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })
+        .unwrap()
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .unwrap();
+    let req_id = id.as_str().to_owned();
+    assert_eq!(
+        rows,
+        vec![
+            (
+                req_id.clone(),
+                "0xabcd".into(),
+                "success".into(),
+                Some("sig".into()),
+                None
+            ),
+            (
+                req_id,
+                "0xabcd".into(),
+                "failed".into(),
+                None,
+                Some("boom".into())
+            ),
+        ]
+    );
+}
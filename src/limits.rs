@@ -0,0 +1,385 @@
+//! Faucet rate limiting module.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::config::{self, ApiKey, Quota};
+use crate::id::ReqId;
+
+/// Upper bound of tracked clients before a full sweep of stale entries.
+const MAX_SUBJECTS: usize = 10_000;
+
+/// Window of the API key quotas.
+const KEY_WINDOW: Duration = Duration::from_secs(86400);
+
+lazy_static::lazy_static! {
+    static ref LIMITER: Mutex<Limiter> = Mutex::new(Limiter::default());
+    static ref KEYS: Mutex<Limiter> = Mutex::new(Limiter::default());
+}
+
+/// Kind of requested token; amounts are accounted separately per kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Neon,
+    Erc20,
+    Sol,
+    Spl,
+}
+
+/// Represents the rate limiting errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Too many requests for {0}")]
+    TooManyRequests(String, Duration),
+
+    #[error("Amount limit exceeded for {0}")]
+    AmountExceeded(String, Duration),
+}
+
+impl Error {
+    /// Returns the time after which the request may be accepted.
+    pub fn retry_after(&self) -> Duration {
+        match self {
+            Error::TooManyRequests(_, d) | Error::AmountExceeded(_, d) => *d,
+        }
+    }
+
+    /// Returns the time as whole seconds (rounded up) for the Retry-After header.
+    pub fn retry_after_secs(&self) -> u64 {
+        let d = self.retry_after();
+        d.as_secs() + u64::from(d.subsec_nanos() > 0)
+    }
+}
+
+/// Represents quota taken by an admitted request.
+pub struct Reservation {
+    id: ReqId,
+    limiter: &'static Mutex<Limiter>,
+    subjects: Vec<String>,
+    time: Instant,
+    token: Token,
+    amount: u64,
+}
+
+impl Reservation {
+    /// Returns the quota back (the airdrop has failed).
+    pub fn release(self) {
+        info!("{} Releasing quota of {:?}", self.id, self.subjects);
+        self.limiter
+            .lock()
+            .unwrap()
+            .release(&self.subjects, self.time, self.token, self.amount);
+    }
+}
+
+/// Checks the request against wallet and IP quotas and records it if allowed.
+/// `amount` is expected in whole tokens.
+pub fn check(
+    id: &ReqId,
+    token: Token,
+    wallet: &str,
+    ip: Option<&str>,
+    amount: u64,
+) -> Result<Option<Reservation>, Error> {
+    if !config::limits_enabled() {
+        return Ok(None);
+    }
+
+    let window = Duration::from_secs(config::limits_window());
+    let mut subjects = vec![(
+        format!("wallet {}", wallet.to_lowercase()),
+        config::limits_wallet(),
+    )];
+    if let Some(ip) = ip {
+        subjects.push((format!("ip {}", ip), config::limits_ip()));
+    }
+
+    reserve(id, &LIMITER, window, &subjects, token, amount).map(Some)
+}
+
+/// Checks the request against the daily quota of the API key and records it if allowed.
+/// `amount` is expected in whole tokens.
+pub fn check_key(
+    id: &ReqId,
+    token: Token,
+    key: &ApiKey,
+    amount: u64,
+) -> Result<Reservation, Error> {
+    let subjects = [(format!("key {}", key.name), key.daily_quota.clone())];
+    reserve(id, &KEYS, KEY_WINDOW, &subjects, token, amount)
+}
+
+fn reserve(
+    id: &ReqId,
+    limiter: &'static Mutex<Limiter>,
+    window: Duration,
+    subjects: &[(String, Quota)],
+    token: Token,
+    amount: u64,
+) -> Result<Reservation, Error> {
+    let time = Instant::now();
+    limiter
+        .lock()
+        .unwrap()
+        .check(time, window, subjects, token, amount)
+        .map_err(|e| {
+            warn!("{} {} (retry after {:?})", id, e, e.retry_after());
+            e
+        })?;
+    Ok(Reservation {
+        id: id.clone(),
+        limiter,
+        subjects: subjects.iter().map(|(s, _)| s.clone()).collect(),
+        time,
+        token,
+        amount,
+    })
+}
+
+/// Returns IP address of the client connected from the `peer` address.
+/// The X-Forwarded-For header is respected only when the peer is a trusted proxy:
+/// the client is the rightmost address which is not a trusted proxy.
+pub fn client_ip(peer: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+    real_ip(peer, forwarded_for, &config::limits_trusted_proxies())
+}
+
+fn real_ip(peer: IpAddr, forwarded_for: Option<&str>, trusted: &[IpAddr]) -> IpAddr {
+    let mut ip = peer;
+    let forwarded = forwarded_for.unwrap_or_default().rsplit(',');
+    for addr in forwarded {
+        if !trusted.contains(&ip) {
+            break;
+        }
+        match addr.trim().parse::<IpAddr>() {
+            Ok(addr) => ip = addr,
+            Err(_) => break,
+        }
+    }
+    ip
+}
+
+/// Represents an accepted request.
+#[derive(Debug)]
+struct Request {
+    time: Instant,
+    token: Token,
+    amount: u64,
+}
+
+/// Keeps history of accepted requests per client within the sliding window.
+#[derive(Debug, Default)]
+struct Limiter {
+    history: HashMap<String, VecDeque<Request>>,
+}
+
+impl Limiter {
+    /// Checks all subjects; records the request for each of them only if all pass.
+    fn check(
+        &mut self,
+        now: Instant,
+        window: Duration,
+        subjects: &[(String, Quota)],
+        token: Token,
+        amount: u64,
+    ) -> Result<(), Error> {
+        for (subject, quota) in subjects {
+            if let Some(history) = self.history.get_mut(subject) {
+                prune(history, now, window);
+                check_quota(subject, history, quota, now, window, token, amount)?;
+            } else {
+                check_quota(subject, &VecDeque::new(), quota, now, window, token, amount)?;
+            }
+        }
+
+        for (subject, _) in subjects {
+            self.history
+                .entry(subject.clone())
+                .or_default()
+                .push_back(Request {
+                    time: now,
+                    token,
+                    amount,
+                });
+        }
+
+        if self.history.len() > MAX_SUBJECTS {
+            self.history.retain(|_, history| {
+                prune(history, now, window);
+                !history.is_empty()
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Removes the request recorded by `check` for each of the subjects.
+    fn release(&mut self, subjects: &[String], time: Instant, token: Token, amount: u64) {
+        for subject in subjects {
+            if let Some(history) = self.history.get_mut(subject) {
+                let recorded = history
+                    .iter()
+                    .position(|r| r.time == time && r.token == token && r.amount == amount);
+                if let Some(i) = recorded {
+                    history.remove(i);
+                }
+            }
+        }
+    }
+}
+
+/// Drops requests which are out of the window.
+fn prune(history: &mut VecDeque<Request>, now: Instant, window: Duration) {
+    while let Some(r) = history.front() {
+        if now.saturating_duration_since(r.time) >= window {
+            history.pop_front();
+        } else {
+            break;
+        }
+    }
+}
+
+/// Checks a single subject against its quota.
+fn check_quota(
+    subject: &str,
+    history: &VecDeque<Request>,
+    quota: &Quota,
+    now: Instant,
+    window: Duration,
+    token: Token,
+    amount: u64,
+) -> Result<(), Error> {
+    let expires = |r: &Request| (r.time + window).saturating_duration_since(now);
+
+    if quota.requests > 0 && history.len() as u64 >= quota.requests {
+        let retry_after = history.front().map_or(window, expires);
+        return Err(Error::TooManyRequests(subject.into(), retry_after));
+    }
+
+    let cap = match token {
+        Token::Neon => quota.neon,
+        Token::Erc20 => quota.erc20,
+        Token::Sol => quota.sol,
+        Token::Spl => quota.spl,
+    };
+    if cap == 0 {
+        return Ok(());
+    }
+
+    let same_token = || history.iter().filter(|r| r.token == token);
+    let mut total = same_token().fold(amount, |sum, r| sum.saturating_add(r.amount));
+    if total <= cap {
+        return Ok(());
+    }
+
+    let mut retry_after = window;
+    if amount <= cap {
+        for r in same_token() {
+            total -= r.amount;
+            if total <= cap {
+                retry_after = expires(r);
+                break;
+            }
+        }
+    }
+    Err(Error::AmountExceeded(subject.into(), retry_after))
+}
+
+#[test]
+fn test_limiter_requests() {
+    let window = Duration::from_secs(60);
+    let quota = Quota {
+        requests: 2,
+        ..Quota::default()
+    };
+    let subjects = vec![("wallet A".to_string(), quota)];
+    let mut limiter = Limiter::default();
+    let t0 = Instant::now();
+
+    assert!(limiter.check(t0, window, &subjects, Token::Neon, 1).is_ok());
+    let t1 = t0 + Duration::from_secs(10);
+    assert!(limiter.check(t1, window, &subjects, Token::Erc20, 1).is_ok());
+    let t2 = t0 + Duration::from_secs(20);
+    let r = limiter.check(t2, window, &subjects, Token::Neon, 1);
+    assert!(matches!(r, Err(Error::TooManyRequests(_, _))));
+    assert_eq!(r.err().unwrap().retry_after(), Duration::from_secs(40));
+
+    let t3 = t0 + Duration::from_secs(60);
+    assert!(limiter.check(t3, window, &subjects, Token::Neon, 1).is_ok());
+}
+
+#[test]
+fn test_limiter_amount() {
+    let window = Duration::from_secs(60);
+    let wallet = Quota {
+        neon: 10,
+        ..Quota::default()
+    };
+    let ip = Quota {
+        requests: 1,
+        ..Quota::default()
+    };
+    let mut limiter = Limiter::default();
+    let t0 = Instant::now();
+
+    let subjects = vec![("wallet A".to_string(), wallet.clone())];
+    assert!(limiter.check(t0, window, &subjects, Token::Neon, 6).is_ok());
+    let t1 = t0 + Duration::from_secs(30);
+    assert!(limiter.check(t1, window, &subjects, Token::Neon, 4).is_ok());
+    assert!(limiter.check(t1, window, &subjects, Token::Erc20, 100).is_ok());
+
+    let t2 = t0 + Duration::from_secs(45);
+    let r = limiter.check(t2, window, &subjects, Token::Neon, 3);
+    assert!(matches!(r, Err(Error::AmountExceeded(_, _))));
+    assert_eq!(r.err().unwrap().retry_after(), Duration::from_secs(15));
+
+    let r = limiter.check(t2, window, &subjects, Token::Neon, 11);
+    assert_eq!(r.err().unwrap().retry_after(), window);
+
+    // Rejection by the second subject must not be recorded for the first one
+    let subjects = vec![
+        ("wallet B".to_string(), wallet),
+        ("ip 127.0.0.1".to_string(), ip),
+    ];
+    assert!(limiter.check(t2, window, &subjects, Token::Neon, 5).is_ok());
+    assert!(limiter.check(t2, window, &subjects, Token::Neon, 5).is_err());
+    assert_eq!(limiter.history["wallet B"].len(), 1);
+}
+
+#[test]
+fn test_limiter_release() {
+    let window = Duration::from_secs(60);
+    let quota = Quota {
+        requests: 1,
+        ..Quota::default()
+    };
+    let subjects = vec![("wallet A".to_string(), quota)];
+    let mut limiter = Limiter::default();
+    let t0 = Instant::now();
+
+    assert!(limiter.check(t0, window, &subjects, Token::Neon, 1).is_ok());
+    assert!(limiter
+        .check(t0, window, &subjects, Token::Neon, 1)
+        .is_err());
+    limiter.release(&["wallet A".to_string()], t0, Token::Neon, 1);
+    assert!(limiter.check(t0, window, &subjects, Token::Neon, 1).is_ok());
+}
+
+#[test]
+fn test_real_ip() {
+    let peer: IpAddr = "10.0.0.1".parse().unwrap();
+    let proxies: Vec<IpAddr> = vec![peer, "10.0.0.2".parse().unwrap()];
+    let client = |forwarded, trusted| real_ip(peer, forwarded, trusted).to_string();
+
+    assert_eq!(client(Some("1.1.1.1"), &[]), "10.0.0.1");
+    assert_eq!(client(None, &proxies), "10.0.0.1");
+    assert_eq!(client(Some("1.1.1.1"), &proxies), "1.1.1.1");
+    assert_eq!(
+        client(Some("6.6.6.6, 1.1.1.1, 10.0.0.2"), &proxies),
+        "1.1.1.1"
+    );
+    assert_eq!(client(Some("garbage"), &proxies), "10.0.0.1");
+}
@@ -4,16 +4,32 @@
 #![deny(warnings)]
 
 mod active_requests;
+mod auth;
+mod captcha;
 mod cli;
 mod config;
 mod erc20_tokens;
+mod error;
 mod ethereum;
+mod gas;
+mod health;
 mod id;
+mod jobs;
+mod ledger;
+mod limits;
 mod log;
 mod manual;
+mod metrics;
 mod neon_token;
+mod nonce;
+mod operators;
+mod pool;
+mod reload;
 mod server;
+mod signer;
+mod sol_token;
 mod solana;
+mod spl_tokens;
 mod version;
 
 use eyre::Result;
@@ -113,6 +129,9 @@ async fn run(config_file: &Path, workers: usize) -> Result<()> {
     config::show();
 
     if config::web3_enabled() || config::solana_enabled() {
+        ledger::init()?;
+        reload::spawn(config_file)?;
+        neon_token::spawn_params_refresh();
         server::start(workers).await?;
     }
 
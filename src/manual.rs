@@ -20,7 +20,15 @@ Several endpoints are supported.
 | request_neon_in_galans | POST | JSON | Requests NEON tokens, amount in galans (fractions)
 | request_neon | POST | JSON | Requests NEON tokens
 | request_erc20_list | GET | | Requests list of available ERC20 tokens
+| v1/request_erc20_list | GET | | Requests list of ERC20 tokens with metadata and balances
 | request_erc20 | POST | JSON | Requests ERC20 tokens
+| request_sol | POST | JSON | Requests SOL to a Solana wallet
+| request_spl_list | GET | | Requests list of available SPL mints
+| request_spl | POST | JSON | Requests SPL tokens to a Solana wallet
+| request_status/{id} | GET | | Requests status of an airdrop job
+| health | GET | | Liveness probe: responds while the server is running
+| ready | GET | | Readiness probe: checks upstreams and balances
+| metrics | GET | | Requests metrics in Prometheus text format
 |-
 
 Examples of JSON workload:
@@ -30,6 +38,112 @@ Examples of JSON workload:
 ```
 { "wallet": "0x4570e07200b6332989Dc04fA2a671b839D26eF0E", "token_addr": "0x00000000000000000000000000000000CafeBabe", "amount": 10 }
 ```
+```
+{ "wallet": "0x4570e07200b6332989Dc04fA2a671b839D26eF0E", "token": "USDC" }
+```
+```
+{ "wallet": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin", "amount": 500000000, "in_lamports": true }
+```
+```
+{ "wallet": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin", "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "amount": 10 }
+```
+
+On success an airdrop request responds with a JSON object describing the transactions.
+NEON and SOL airdrops return the Solana signature and slot:
+```
+{ "signature": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW", "slot": 1234 }
+```
+SPL airdrop returns a signature and slot per mint. When some mints of an airdrop of all
+mints fail, the others are still sent and the failed ones are reported:
+```
+{ "transactions": [{ "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "signature": "5VER...kQUW", "slot": 1234 },
+                   { "mint": "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", "code": "upstream_rpc", "error": "Upstream RPC request failed" }] }
+```
+ERC20 airdrop returns a hash, block number and status per token, failed tokens are
+reported the same way:
+```
+{ "transactions": [{ "token": "0x00000000000000000000000000000000CafeBabe", "hash": "0x5c50...0c9e", "block": 567, "status": "confirmed" },
+                   { "token": "0x00000000000000000000000000000000DeadBeef", "status": "failed", "code": "transaction_reverted", "error": "Transaction is reverted" }] }
+```
+
+On failure the server responds with a JSON object containing a stable error code,
+a human-readable message and the request id (to be quoted in support requests):
+```
+{ "code": "amount_over_limit", "message": "Requested value 100 exceeds the limit 10", "req_id": "1a2b3c4" }
+```
+
+|:-:|:-:|-
+|**Code**|**Status**|**Description**|
+|:-|:-:|-
+| bad_body | 400 | Request body is not a valid UTF-8 text
+| bad_json | 400 | Request body is not a valid JSON workload
+| invalid_address | 400 | Wallet address cannot be parsed
+| unknown_token | 400 | Requested token is not configured
+| amount_over_limit | 400 | Requested amount exceeds the limit of a single request
+| insufficient_balance | 503 | The faucet has run out of tokens
+| insufficient_allowance | 503 | An operator has not approved the batch contract to spend a token
+| rate_limited | 429 | Quota of the wallet or IP is exhausted (see `Retry-After`)
+| captcha_failed | 403 | Captcha token is missing or rejected by the verifier
+| captcha_unavailable | 503 | Captcha verifier cannot be reached
+| unauthorized | 401 | API key is missing (anonymous access is disabled) or invalid
+| forbidden | 403 | API key is not allowed to use the endpoint
+| unknown_job | 404 | Requested job is unknown or expired
+| transaction_reverted | 502 | Ethereum transaction is reverted (e.g. rejected by the token)
+| transaction_stuck | 503 | Ethereum transaction is not mined in time
+| upstream_rpc | 502 | Solana or Ethereum RPC request failed
+| internal | 500 | Any other failure
+|-
+
+When **rpc**.async_airdrop is enabled, airdrop requests are enqueued and the server
+responds immediately with `202 Accepted` and the job id, e.g. `{"job_id": "5f0c6d2a9e8b4b1c8f3e7a6d2c1b0a9f"}`.
+The job status is one of `queued`, `sent`, `confirmed`, `partial` or `failed`. A finished
+job keeps the `receipt` (the response of a synchronous request): it is `confirmed` only if
+every transfer is confirmed, `sent` if some transfer is not confirmed yet and `partial` if
+some transfers have failed (see their errors in the receipt). A job not finished within
+4 hours is `failed`; finished jobs are kept for an hour.
+```
+{ "id": "5f0c6d2a9e8b4b1c8f3e7a6d2c1b0a9f", "status": "confirmed", "transactions": ["5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW"], "receipt": { "signature": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW", "slot": 1234 } }
+```
+
+A client having an API key sends it in the `Authorization: Bearer <key>` header.
+Requests with a key are limited by the per-request and daily caps of the key instead
+of the default limits, and skip captcha verification.
+
+When captcha is enabled, airdrop requests to the configured endpoints must carry
+a token issued by the captcha widget (hCaptcha, reCAPTCHA or Turnstile) in the
+`X-Captcha-Token` header. The token is checked with the verifier before the airdrop.
+
+When rate limiting is enabled, an airdrop request exceeding a quota is rejected
+with code `rate_limited` and the `Retry-After` header (in seconds).
+A request which fails afterwards (e.g. bad amount or RPC error) does not consume the quota.
+The IP of a client is the address of the connection. The `X-Forwarded-For` header
+is followed only through the addresses listed in **limits**.trusted_proxies.
+
+The `ready` endpoint checks reachability of Solana and Web3 RPC, loading of NEON
+parameters from the EVM Loader, the operator's token account and ERC20 balances
+(only for enabled sections). It responds with `200 OK` when all checks pass and
+`503 Service Unavailable` otherwise, with a JSON breakdown:
+```
+{ "ready": false, "checks": { "neon_params": { "ok": true }, "solana_rpc": { "ok": true, "details": "1.9.12" }, "token_account": { "ok": false, "error": "Insufficient balance of the faucet" } } }
+```
+The container healthcheck (`health_check_faucet.sh`) probes liveness with `health`,
+so brief upstream outages do not restart the faucet; use `ready` for readiness probes.
+
+The `metrics` endpoint exposes the following metrics:
+
+|:-:|:-:|-
+|**Metric**|**Type**|**Description**|
+|:-|:-:|-
+| faucet_requests_total | counter | Served requests by `endpoint` and `status`
+| faucet_request_duration_seconds | histogram | Latency of requests by `endpoint`
+| faucet_airdropped_total | counter | Airdropped amount (in whole tokens) by `token`
+| faucet_active_requests | gauge | Number of requests being processed
+| faucet_operator_balance | gauge | Balance (in whole tokens) by `operator` and `token`
+| faucet_rpc_errors_total | counter | Failed upstream requests by `rpc` (`solana` or `web3`)
+|-
+
+Balances of the operators are queried at most every 30 seconds, more frequent scrapes
+get the last queried balances.
 
 Example of ping request with **curl** utility:
 ```
@@ -41,11 +155,36 @@ Example of version request with **curl** utility:
 curl -i -X GET 'http://localhost:3333/request_version'
 ```
 
+Example of job status request with **curl** utility:
+```
+curl -i -X GET 'http://localhost:3333/request_status/5f0c6d2a9e8b4b1c8f3e7a6d2c1b0a9f'
+```
+
+Example of readiness request with **curl** utility:
+```
+curl -i -X GET 'http://localhost:3333/ready'
+```
+
+Example of metrics request with **curl** utility:
+```
+curl -i -X GET 'http://localhost:3333/metrics'
+```
+
 Example of request of list of ERC20 with **curl** utility:
 ```
 curl -i -X GET 'http://localhost:3333/request_erc20_list'
 ```
 
+The versioned list describes each token, the largest amount per request and the
+remaining balance of all operators (in whole tokens; `mint` marks minted tokens).
+Token metadata is read once, balances are queried at most every 30 seconds (the same
+balances are reported by `metrics` and `ready`); `balance` is `null` if a query of the
+token has failed, `name`, `symbol` and `decimals` are `null` until the token is checked:
+```
+curl -i -X GET 'http://localhost:3333/v1/request_erc20_list'
+{ "tokens": [{ "address": "0x00000000000000000000000000000000CafeBabe", "name": "Test Token", "symbol": "TST", "decimals": 9, "max_amount": 1000, "balance": 95000.0, "mint": false }] }
+```
+
 Example of NEON drop request with **curl** utility:
 ```
 curl -i -X POST \
@@ -54,6 +193,23 @@ curl -i -X POST \
     'http://localhost:3333/request_neon'
 ```
 
+Example of SOL drop request with **curl** utility:
+```
+curl -i -X POST \
+    -d '{"wallet": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin", \
+         "amount": 1}' \
+    'http://localhost:3333/request_sol'
+```
+
+Example of SPL drop request with **curl** utility:
+```
+curl -i -X POST \
+    -d '{"wallet": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin", \
+         "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", \
+         "amount": 1}' \
+    'http://localhost:3333/request_spl'
+```
+
 Example of ERC20 drop request with **curl** utility:
 ```
 curl -i -X POST \
@@ -75,17 +231,85 @@ The configuration file should be in TOML format.
 | **rpc**.bind | Local interface TCP address
 | **rpc**.port | TCP port to listen
 | **rpc**.allowed_origins | List of client URLs that can send requests
+| **rpc**.async_airdrop | Flag to enqueue airdrops and respond with job id instead of waiting
+| **rpc**.watch_config | Flag to reload the config on change of the file
 | **web3**.enable | Flag to on/off the entire **web3** section
 | **web3**.rpc_url | Ethereum network endpoint
 | **web3**.private_key | Ethereum private key to support operations
 | **web3**.tokens | List of available ERC20 token addresses
 | **web3**.max_amount | Largest amount of ERC20 tokens to distribute with a single request
+| **web3**.token.address | ERC20 token address (table array `[[web3.token]]`, in addition to **web3**.tokens)
+| **web3**.token.alias | Name of the token in requests, like a symbol (optional)
+| **web3**.token.max_amount | Largest amount of the token per request (**web3**.max_amount if missing)
+| **web3**.token.default_amount | Amount of the token for requests without one (optional)
+| **web3**.token.enable | Flag to on/off the token (`true` by default)
+| **web3**.private_keys | List of private keys of additional Ethereum operators
+| **web3**.operator_selection | Choice of the operator: `round_robin` (default) or `balance`
+| **web3**.operator_min_balance | Smallest native (gas) balance of an operator to be chosen (0 disables the check)
+| **web3**.replace_timeout | Seconds to wait for a transaction before replacing it with a higher gas price (default 60, 0 disables)
+| **web3**.confirmations | Number of blocks on top of a transfer to consider it confirmed
+| **web3**.confirmation_timeout | Seconds to wait for confirmations before responding with a submitted transfer (default 120, must be positive)
+| **web3**.gas_multiplier | Safety multiplier of the estimated gas limit (1.2 by default)
+| **web3**.max_gas_price | Cap of the gas price in gwei (0 means no cap)
+| **web3**.max_fee_per_gas | Cap of the EIP-1559 max fee per gas in gwei (0 means legacy transactions)
+| **web3**.max_priority_fee_per_gas | EIP-1559 priority fee per gas in gwei
+| **web3**.batch_contract | Address of the trusted batch contract transferring all tokens in a single transaction (optional; operators must approve it to spend the tokens)
+| **web3**.mint_tokens | List of token addresses minted to the recipient instead of transferred
+| **web3**.mint_function | Name of the mint function taking `(address, uint256)` (`mint` by default)
+| **web3**.mint_abi | ABI file of the mint function (required for **web3**.mint_tokens)
+| **web3**.signer.backend | Source of the Ethereum key: `local` (default, **web3**.private_key), `keystore`, `env` or `remote`
+| **web3**.signer.keystore | Encrypted V3 keystore file (for `keystore`)
+| **web3**.signer.passphrase_env | Environment variable with passphrase of the keystore (for `keystore`)
+| **web3**.signer.key_env | Environment variable with the hex private key (for `env`)
+| **web3**.signer.url | URL of the remote signer supporting `eth_signTransaction` (for `remote`)
+| **web3**.signer.public | Address of the key in the remote signer (for `remote`)
 | **solana**.enable | Flag to on/off the entire **solana** section
 | **solana**.url | Solana network endpoint
 | **solana**.commitment | Solana client commitment level
 | **solana**.operator_keyfile | Solana keyfile to support operations
+| **solana**.operator_keyfiles | List of keyfiles of additional Solana operators
+| **solana**.operator_selection | Choice of the operator: `round_robin` (default) or `balance`
+| **solana**.operator_min_balance | Smallest SOL (gas) balance of an operator to be chosen (0 disables the check)
 | **solana**.evm_loader | Address of the EVM Loader program
 | **solana**.max_amount | Largest amount of NEONs to distribute with a single request
+| **solana**.sol_max_amount | Largest amount of SOL to distribute with a single request
+| **solana**.spl_mints | List of available SPL mint addresses
+| **solana**.spl_max_amount | Largest amount of SPL tokens to distribute with a single request
+| **solana**.neon_params_refresh | Interval in seconds of re-reading NEON parameters from the EVM Loader (0 disables)
+| **solana**.priority_fee | Priority fee in lamports added to the compute budget fee of NEON deposits
+| **solana**.evm_loader_elf | Local ELF file of the EVM Loader to read NEON parameters from (instead of the cluster)
+| **solana**.signer.backend | Source of the Solana key: `local` (default, **solana**.operator_keyfile), `keystore`, `env` or `remote`
+| **solana**.signer.keystore | Encrypted V3 keystore file with the keypair or secret key (for `keystore`)
+| **solana**.signer.passphrase_env | Environment variable with passphrase of the keystore (for `keystore`)
+| **solana**.signer.key_env | Environment variable with the keypair as JSON array or base58 string (for `env`)
+| **solana**.signer.url | URL of the remote signer (for `remote`)
+| **solana**.signer.public | Public key of the key in the remote signer (for `remote`)
+| **solana**.neon_params | Table of NEON parameters overriding ones of the EVM Loader (see below)
+| **limits**.enable | Flag to on/off rate limiting of airdrop requests
+| **limits**.window | Length of the sliding window in seconds
+| **limits**.wallet.requests | Largest number of airdrops to a single wallet within the window
+| **limits**.wallet.neon | Largest cumulative amount of NEONs to a single wallet within the window
+| **limits**.wallet.erc20 | Largest cumulative amount of ERC20 tokens to a single wallet within the window
+| **limits**.wallet.sol | Largest cumulative amount of SOL to a single wallet within the window
+| **limits**.wallet.spl | Largest cumulative amount of SPL tokens to a single wallet within the window
+| **limits**.ip.requests | Largest number of airdrops requested from a single IP within the window
+| **limits**.ip.neon | Largest cumulative amount of NEONs requested from a single IP within the window
+| **limits**.ip.erc20 | Largest cumulative amount of ERC20 tokens requested from a single IP within the window
+| **limits**.ip.sol | Largest cumulative amount of SOL requested from a single IP within the window
+| **limits**.ip.spl | Largest cumulative amount of SPL tokens requested from a single IP within the window
+| **limits**.trusted_proxies | IP addresses of reverse proxies whose X-Forwarded-For header is trusted
+| **storage**.enable | Flag to on/off the ledger of airdrops
+| **storage**.path | Path to the SQLite database file of the ledger
+| **captcha**.enable | Flag to on/off captcha verification of airdrop requests
+| **captcha**.verify_url | URL of the captcha verifier (siteverify endpoint of the provider)
+| **captcha**.secret | Secret key shared with the captcha provider
+| **captcha**.endpoints | List of endpoints requiring captcha (all airdrop endpoints by default)
+| **auth**.anonymous | Flag to allow airdrop requests without API key (`true` by default)
+| **auth**.keys.name | Name of the API key owner
+| **auth**.keys.hash | SHA-256 hash (hex) of the API key
+| **auth**.keys.max_amount | Largest amount of tokens to distribute with a single request
+| **auth**.keys.daily_quota | Caps within 24 hours (same fields as **limits**.wallet)
+| **auth**.keys.endpoints | List of allowed endpoints (all airdrop endpoints if empty)
 |-
 
 Example of the configuration file contents:
@@ -102,6 +326,37 @@ private_key = "0x0000000000000000000000000000000000000000000000000000000000000Ac
 tokens = ["0x00000000000000000000000000000000CafeBabe",
           "0x00000000000000000000000000000000DeadBeef"]
 max_amount = 1000
+# private_keys = ["0x0000000000000000000000000000000000000000000000000000000000000Bad"]
+operator_selection = "round_robin"
+operator_min_balance = 0
+replace_timeout = 60
+confirmations = 1
+confirmation_timeout = 120
+gas_multiplier = 1.2
+max_gas_price = 0
+# max_fee_per_gas = 500
+# max_priority_fee_per_gas = 2
+# batch_contract = "0x00000000000000000000000000000000000Ba7C4"
+mint_tokens = ["0x00000000000000000000000000000000DeadBeef"]
+mint_function = "mint"
+mint_abi = "/opt/faucet/Mintable.abi"
+
+[[web3.token]]
+address = "0x00000000000000000000000000000000Deadc0de"
+alias = "USDC"
+max_amount = 100
+default_amount = 10
+
+[[web3.token]]
+address = "0x00000000000000000000000000000000Feedf00d"
+alias = "MEME"
+max_amount = 1000000
+enable = false
+
+# [web3.signer]
+# backend = "keystore"
+# keystore = "/run/secrets/web3_keystore.json"
+# passphrase_env = "FAUCET_WEB3_PASSPHRASE"
 
 [solana]
 enable = true
@@ -109,11 +364,159 @@ url = "http://localhost:8899"
 commitment = "processed"
 evm_loader = "EvmLoaderId11111111111111111111111111111111"
 operator_keyfile = "operator_id.json"
+# operator_keyfiles = ["operator2_id.json"]
+operator_selection = "balance"
+operator_min_balance = 0.1
 max_amount = 10
+sol_max_amount = 1
+spl_mints = ["EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"]
+spl_max_amount = 100
+neon_params_refresh = 600
+priority_fee = 0
+# evm_loader_elf = "evm_loader.so"
+
+# [solana.signer]
+# backend = "remote"
+# url = "http://signer:8080"
+# public = "9kPRbbwKL5SYELF4cZqWWFmP88QkKys51DoaUBx8eK73"
+
+# [solana.neon_params]
+# seed_version = 1
+# token_mint = "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g"
+# token_mint_decimals = 9
+# compute_units = 500000
+# heap_frame = 262144
+# additional_fee = 0
+
+[limits]
+enable = true
+window = 86400
+# trusted_proxies = ["10.0.0.1"]
+
+[limits.wallet]
+requests = 5
+neon = 50
+erc20 = 5000
+
+[limits.ip]
+requests = 20
+
+[storage]
+enable = true
+path = "faucet.db"
+
+[captcha]
+enable = true
+verify_url = "https://hcaptcha.com/siteverify"
+secret = "0x0000000000000000000000000000000000000000"
+endpoints = ["request_neon", "request_erc20"]
+
+[auth]
+anonymous = true
+
+[[auth.keys]]
+name = "ci"
+hash = "2bb80d537b1da3e38bd30361aa855686bde0eacd7162fef6a25fe97bf527a25b"
+max_amount = 100
+endpoints = ["request_neon"]
+
+[auth.keys.daily_quota]
+neon = 10000
 ```
 
+Zero value of **solana**.sol_max_amount disables SOL airdrops.
+Associated token accounts of SPL recipients are created (and paid) by the operator when missing.
+Zero value of a **limits** quota means "unlimited".
+The hash of an API key can be computed with `echo -n <key> | sha256sum`.
+
+The ledger keeps every airdrop (request id, wallet, token, amount, transaction,
+status, client IP and timestamps) in the table `airdrops` of the database.
+
 The configuration file is optional and, if present, can be incomplete
 (default values or environment variables will be used in such cases).
+
+The running service reloads the configuration file and environment variables on
+SIGHUP (and on change of the file when **rpc**.watch_config is enabled). An invalid
+configuration is rejected and the current one is kept. RPC clients and operator keys
+are kept between requests and recreated after a reload. Changes of **rpc**.bind,
+**rpc**.port and **storage** take effect after restart only.
+
+Operator keys are taken from the source selected by **web3**.signer and **solana**.signer:
+
+* `local` — **web3**.private_key and **solana**.operator_keyfile (plaintext);
+* `keystore` — an encrypted Ethereum V3 keystore, unlocked by a passphrase from the environment;
+* `env` — a private key injected into the environment;
+* `remote` — a signer service keeping the key. For Ethereum it must support the JSON-RPC
+  method `eth_signTransaction`. For Solana it gets `POST <url>/sign` with the body
+  `{ "pubkey": "<base58>", "message": "<base64>" }` and replies `{ "signature": "<base58>" }`.
+  Requests to the remote signer time out after 10 seconds.
+
+The Docker image contains no operator keys. For the `local` backend mount the keypair
+at **solana**.operator_keyfile (`/root/.config/solana/id.json` in the bundled `faucet.conf`),
+e.g. `docker run -v /path/to/id.json:/root/.config/solana/id.json:ro ...`; otherwise select
+another backend, e.g. `FAUCET_SOLANA_SIGNER_BACKEND=env` with the key in the environment.
+`run-faucet.sh` checks SOL and NEON balances of a mounted keypair before start.
+
+Additional operators are listed in **web3**.private_keys and **solana**.operator_keyfiles.
+Each airdrop is sent by one operator chosen in turn (`round_robin`) or by the highest
+balance of the airdropped asset (`balance`): lamports for SOL, the token account for NEON
+and SPL tokens, the ERC20 balance for transferred (not minted) tokens. Operators holding
+less than the requested amount or with gas balance (SOL or native) below the minimum are
+skipped; if none is left the airdrop is rejected with `503 Service Unavailable`. The `ready` endpoint reports balances
+of all operators in the `solana_operators` and `web3_operators` checks.
+
+Nonces of Ethereum transactions are assigned locally per operator, so concurrent
+transfers do not conflict. The nonce of a transaction failed to be sent is assigned again
+to the next transfer, and the nonce is re-read from the pending transaction count after a
+failed transaction once no other transaction of the operator is pending. A transaction not mined within **web3**.replace_timeout seconds is
+replaced by one with the same nonce and 20% higher gas price (at most 3 times).
+
+The gas limit of an ERC20 transfer is estimated by the network and multiplied by
+**web3**.gas_multiplier. Legacy transactions use the gas price of the network capped by
+**web3**.max_gas_price. When **web3**.max_fee_per_gas is set, EIP-1559 transactions are
+sent instead, with the max fee of twice the base fee plus the priority fee (within the cap).
+Replacements never exceed the caps.
+
+A transfer has status `confirmed` when its block has **web3**.confirmations blocks on top
+of it (0 means mined). If that does not happen within **web3**.confirmation_timeout seconds,
+the faucet responds with status `submitted` and the transaction hash. A reverted transfer
+is an error. A failed transfer carries the error `code` and a public message; the details
+are written to the log and the ledger.
+
+ERC20 tokens are listed in **web3**.tokens (sharing **web3**.max_amount) and in the table
+array `[[web3.token]]` with per-token settings. A request names a token by `token_addr` or
+`token`, which accepts an address or an alias (case-insensitive). Without `amount`, the
+default amount of the token is sent; a request without `amount` to a token with no default
+amount is rejected. Disabled tokens are unknown to requests and the token lists.
+
+An ERC20 airdrop without a token transfers all tokens. When **web3**.batch_contract
+is set, it is done atomically by a single transaction of the contract `erc20/Batch.sol`:
+either all tokens arrive or none. The contract must be trusted: the faucet never approves it,
+the owner of each operator approves it to spend the tokens beforehand (e.g. `approve` with
+the budget of the faucet), and anyone controlling the contract can spend that allowance.
+A batch is rejected with `insufficient_allowance` when the remaining allowance of a token is
+less than the amount. Without the contract, tokens are transferred one by
+one and the airdrop fails only if none of them is transferred.
+
+Tokens listed in **web3**.mint_tokens are minted to the recipient by **web3**.mint_function
+of the ABI file **web3**.mint_abi (`erc20/Mintable.abi`, copied to `/opt/faucet` in the
+Docker image, describes `mint(address,uint256)`), so the operator needs no balance of them.
+The file is read when the config is loaded (or reloaded). If the mint call of the operator
+is reverted (e.g. it is not authorized to mint), the token is transferred from its balance
+instead; other errors fail the airdrop of the token. Airdrops including minted tokens do not use the batch contract.
+
+NEON parameters are read from the account of the EVM Loader in the cluster. When
+**solana**.evm_loader_elf is set they are read from the local file instead. Values
+of the **solana**.neon_params table override the read ones; if the table defines at
+least `seed_version`, `token_mint` and `token_mint_decimals`, no ELF is read at all
+(offline setups); a table without them is rejected unless **solana**.evm_loader_elf is set.
+
+NEON parameters of the EVM Loader are also re-read every **solana**.neon_params_refresh
+seconds and after a deposit fails with a program error. Changed values are logged.
+
+NEON deposit transactions start with compute budget instructions requesting the compute
+units and heap frame of the EVM Loader. The fee of the request is the additional fee of
+the EVM Loader plus **solana**.priority_fee.
 "##;
 
 const MANUAL_ENV: &str = r##"
@@ -127,17 +530,70 @@ Environment variables, if present, override portions of the configuration.
 | FAUCET_RPC_BIND | **rpc**.bind | `0.0.0.0`
 | FAUCET_RPC_PORT | **rpc**.port | `3333`
 | FAUCET_RPC_ALLOWED_ORIGINS | **rpc**.allowed_origins | `["http://localhost"]`
+| FAUCET_RPC_ASYNC_AIRDROP | **rpc**.async_airdrop | `false`
+| FAUCET_RPC_WATCH_CONFIG | **rpc**.watch_config | `true`
 | FAUCET_WEB3_ENABLE | **web3**.enable | `true`
 | WEB3_RPC_URL | **web3**.rpc_url | `http://localhost:9090/solana`
 | WEB3_PRIVATE_KEY | **web3**.private_key | `0x00A`
 | NEON_ERC20_TOKENS | **web3**.tokens | `["0x00B", "0x00C"]`
 | NEON_ERC20_MAX_AMOUNT | **web3**.max_amount | `1000`
+| FAUCET_WEB3_PRIVATE_KEYS | **web3**.private_keys | `["0x00D", "0x00E"]`
+| FAUCET_WEB3_OPERATOR_SELECTION | **web3**.operator_selection | `balance`
+| FAUCET_WEB3_OPERATOR_MIN_BALANCE | **web3**.operator_min_balance | `0.5`
+| FAUCET_WEB3_REPLACE_TIMEOUT | **web3**.replace_timeout | `60`
+| FAUCET_WEB3_CONFIRMATIONS | **web3**.confirmations | `1`
+| FAUCET_WEB3_CONFIRMATION_TIMEOUT | **web3**.confirmation_timeout | `120`
+| FAUCET_WEB3_GAS_MULTIPLIER | **web3**.gas_multiplier | `1.5`
+| FAUCET_WEB3_MAX_GAS_PRICE | **web3**.max_gas_price | `1000`
+| FAUCET_WEB3_MAX_FEE_PER_GAS | **web3**.max_fee_per_gas | `500`
+| FAUCET_WEB3_MAX_PRIORITY_FEE_PER_GAS | **web3**.max_priority_fee_per_gas | `2`
+| FAUCET_WEB3_BATCH_CONTRACT | **web3**.batch_contract | `0x00D`
+| FAUCET_WEB3_MINT_TOKENS | **web3**.mint_tokens | `["0x00C"]`
+| FAUCET_WEB3_MINT_FUNCTION | **web3**.mint_function | `mintTo`
+| FAUCET_WEB3_MINT_ABI | **web3**.mint_abi | `/opt/faucet/Mintable.abi`
+| FAUCET_WEB3_SIGNER_BACKEND | **web3**.signer.backend | `env`
+| FAUCET_WEB3_SIGNER_KEYSTORE | **web3**.signer.keystore | `/run/secrets/web3_keystore.json`
+| FAUCET_WEB3_SIGNER_URL | **web3**.signer.url | `http://signer:8550`
+| FAUCET_WEB3_SIGNER_PUBLIC | **web3**.signer.public | `0x4cEf...7a1b`
 | FAUCET_SOLANA_ENABLE | **solana**.enable | `true`
 | SOLANA_URL | **solana**.url | `http://localhost:8899`
 | SOLANA_COMMITMENT | **solana**.commitment | `processed`
 | EVM_LOADER | **solana**.evm_loader | `EvmLoaderId11111111111111111111111111111111`
 | NEON_OPERATOR_KEYFILE | **solana**.operator_keyfile | `operator_id.json`
+| FAUCET_SOLANA_OPERATOR_KEYFILES | **solana**.operator_keyfiles | `["operator2_id.json"]`
+| FAUCET_SOLANA_OPERATOR_SELECTION | **solana**.operator_selection | `round_robin`
+| FAUCET_SOLANA_OPERATOR_MIN_BALANCE | **solana**.operator_min_balance | `0.1`
 | NEON_ETH_MAX_AMOUNT | **solana**.max_amount | `10`
+| FAUCET_SOLANA_SOL_MAX_AMOUNT | **solana**.sol_max_amount | `1`
+| FAUCET_SOLANA_SPL_MINTS | **solana**.spl_mints | `["EPjF...Dt1v"]`
+| FAUCET_SOLANA_SPL_MAX_AMOUNT | **solana**.spl_max_amount | `100`
+| FAUCET_SOLANA_NEON_PARAMS_REFRESH | **solana**.neon_params_refresh | `600`
+| FAUCET_SOLANA_PRIORITY_FEE | **solana**.priority_fee | `5000`
+| FAUCET_SOLANA_EVM_LOADER_ELF | **solana**.evm_loader_elf | `evm_loader.so`
+| FAUCET_SOLANA_SIGNER_BACKEND | **solana**.signer.backend | `keystore`
+| FAUCET_SOLANA_SIGNER_KEYSTORE | **solana**.signer.keystore | `/run/secrets/operator.json`
+| FAUCET_SOLANA_SIGNER_URL | **solana**.signer.url | `http://signer:8080`
+| FAUCET_SOLANA_SIGNER_PUBLIC | **solana**.signer.public | `9kPR...eK73`
+| FAUCET_LIMITS_ENABLE | **limits**.enable | `true`
+| FAUCET_LIMITS_WINDOW | **limits**.window | `86400`
+| FAUCET_LIMITS_WALLET_REQUESTS | **limits**.wallet.requests | `5`
+| FAUCET_LIMITS_WALLET_NEON | **limits**.wallet.neon | `50`
+| FAUCET_LIMITS_WALLET_ERC20 | **limits**.wallet.erc20 | `5000`
+| FAUCET_LIMITS_WALLET_SOL | **limits**.wallet.sol | `5`
+| FAUCET_LIMITS_WALLET_SPL | **limits**.wallet.spl | `500`
+| FAUCET_LIMITS_IP_REQUESTS | **limits**.ip.requests | `20`
+| FAUCET_LIMITS_IP_NEON | **limits**.ip.neon | `200`
+| FAUCET_LIMITS_IP_ERC20 | **limits**.ip.erc20 | `20000`
+| FAUCET_LIMITS_IP_SOL | **limits**.ip.sol | `20`
+| FAUCET_LIMITS_IP_SPL | **limits**.ip.spl | `2000`
+| FAUCET_LIMITS_TRUSTED_PROXIES | **limits**.trusted_proxies | `["10.0.0.1"]`
+| FAUCET_STORAGE_ENABLE | **storage**.enable | `true`
+| FAUCET_STORAGE_PATH | **storage**.path | `faucet.db`
+| FAUCET_CAPTCHA_ENABLE | **captcha**.enable | `true`
+| FAUCET_CAPTCHA_VERIFY_URL | **captcha**.verify_url | `https://hcaptcha.com/siteverify`
+| FAUCET_CAPTCHA_SECRET | **captcha**.secret | `0x00D`
+| FAUCET_CAPTCHA_ENDPOINTS | **captcha**.endpoints | `["request_neon"]`
+| FAUCET_AUTH_ANONYMOUS | **auth**.anonymous | `false`
 | NEON_LOG | | `json`
 | RUST_LOG | | `info`
 |-
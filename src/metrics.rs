@@ -0,0 +1,205 @@
+//! Faucet metrics module (Prometheus text exposition format).
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram buckets (in seconds).
+const BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+lazy_static::lazy_static! {
+    static ref METRICS: Mutex<Metrics> = Mutex::new(Metrics::default());
+}
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; BUCKETS.len()];
+        }
+        for (i, bound) in BUCKETS.iter().enumerate() {
+            if value <= *bound {
+                self.buckets[i] += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    requests: BTreeMap<(String, u16), u64>,
+    latency: BTreeMap<String, Histogram>,
+    airdropped: BTreeMap<String, f64>,
+    rpc_errors: BTreeMap<String, u64>,
+    balances: BTreeMap<(String, String), f64>,
+}
+
+/// Counts a served request and its latency.
+pub fn observe_request(endpoint: &str, status: u16, elapsed: Duration) {
+    let mut m = METRICS.lock().unwrap();
+    *m.requests.entry((endpoint.into(), status)).or_default() += 1;
+    m.latency
+        .entry(endpoint.into())
+        .or_default()
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Counts airdropped amount of a token (in whole tokens).
+pub fn airdropped(token: &str, amount: f64) {
+    *METRICS
+        .lock()
+        .unwrap()
+        .airdropped
+        .entry(token.into())
+        .or_default() += amount;
+}
+
+/// Counts a failed request to an upstream RPC (`solana` or `web3`).
+pub fn rpc_error(rpc: &str) {
+    *METRICS
+        .lock()
+        .unwrap()
+        .rpc_errors
+        .entry(rpc.into())
+        .or_default() += 1;
+}
+
+/// Sets last known balance of the operator (in whole tokens).
+pub fn set_balance(operator: &str, token: &str, amount: f64) {
+    METRICS
+        .lock()
+        .unwrap()
+        .balances
+        .insert((operator.into(), token.into()), amount);
+}
+
+/// Renders all metrics in Prometheus text format.
+pub fn render(active_requests: isize) -> String {
+    let m = METRICS.lock().unwrap();
+    let mut out = String::new();
+
+    header(
+        &mut out,
+        "faucet_requests_total",
+        "counter",
+        "Number of served HTTP requests",
+    );
+    for ((endpoint, status), n) in &m.requests {
+        let _ = writeln!(
+            out,
+            "faucet_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+            endpoint, status, n
+        );
+    }
+
+    header(
+        &mut out,
+        "faucet_request_duration_seconds",
+        "histogram",
+        "Latency of HTTP requests",
+    );
+    for (endpoint, h) in &m.latency {
+        for (bound, n) in BUCKETS.iter().zip(&h.buckets) {
+            let _ = writeln!(
+                out,
+                "faucet_request_duration_seconds_bucket{{endpoint=\"{}\",le=\"{}\"}} {}",
+                endpoint, bound, n
+            );
+        }
+        let _ = writeln!(
+            out,
+            "faucet_request_duration_seconds_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}",
+            endpoint, h.count
+        );
+        let _ = writeln!(
+            out,
+            "faucet_request_duration_seconds_sum{{endpoint=\"{}\"}} {}",
+            endpoint, h.sum
+        );
+        let _ = writeln!(
+            out,
+            "faucet_request_duration_seconds_count{{endpoint=\"{}\"}} {}",
+            endpoint, h.count
+        );
+    }
+
+    header(
+        &mut out,
+        "faucet_airdropped_total",
+        "counter",
+        "Airdropped amount of tokens (in whole tokens)",
+    );
+    for (token, amount) in &m.airdropped {
+        let _ = writeln!(
+            out,
+            "faucet_airdropped_total{{token=\"{}\"}} {}",
+            token, amount
+        );
+    }
+
+    header(
+        &mut out,
+        "faucet_active_requests",
+        "gauge",
+        "Number of requests being processed",
+    );
+    let _ = writeln!(out, "faucet_active_requests {}", active_requests);
+
+    header(
+        &mut out,
+        "faucet_operator_balance",
+        "gauge",
+        "Balance of the operator (in whole tokens)",
+    );
+    for ((operator, token), amount) in &m.balances {
+        let _ = writeln!(
+            out,
+            "faucet_operator_balance{{operator=\"{}\",token=\"{}\"}} {}",
+            operator, token, amount
+        );
+    }
+
+    header(
+        &mut out,
+        "faucet_rpc_errors_total",
+        "counter",
+        "Number of failed requests to upstream RPC",
+    );
+    for (rpc, n) in &m.rpc_errors {
+        let _ = writeln!(out, "faucet_rpc_errors_total{{rpc=\"{}\"}} {}", rpc, n);
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+#[test]
+fn test_render() {
+    observe_request("/request_neon", 200, Duration::from_millis(300));
+    observe_request("/request_neon", 200, Duration::from_secs(2));
+    airdropped("NEON", 1.5);
+
+    let text = render(1);
+    assert!(text.contains("faucet_requests_total{endpoint=\"/request_neon\",status=\"200\"} 2"));
+    assert!(text.contains(
+        "faucet_request_duration_seconds_bucket{endpoint=\"/request_neon\",le=\"0.5\"} 1"
+    ));
+    assert!(text.contains(
+        "faucet_request_duration_seconds_bucket{endpoint=\"/request_neon\",le=\"+Inf\"} 2"
+    ));
+    assert!(text.contains("faucet_airdropped_total{token=\"NEON\"} 1.5"));
+    assert!(text.contains("faucet_active_requests 1"));
+}
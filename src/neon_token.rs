@@ -1,81 +1,307 @@
 //! Faucet NEON token module.
 
-use eyre::{eyre, Result};
-use tracing::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-use crate::{config, ethereum, id::ReqId, solana};
+use eyre::{eyre, Result, WrapErr as _};
+use tracing::{debug, error, info};
+
+use crate::error::Error;
+use crate::operators::{self, SolanaAsset};
+use crate::{config, ethereum, id, id::ReqId, ledger, metrics, pool, solana};
 
 /// Represents packet of information needed for single airdrop operation.
 #[derive(Debug, serde::Deserialize)]
 pub struct Airdrop {
     /// Ethereum address of the recipient.
-    wallet: String,
+    pub wallet: String,
     /// Amount of a token to be received.
-    amount: u64,
+    pub amount: u64,
     /// Specifies amount in whole tokens (false, default) or in 10E-9 fractions (true).
     #[serde(default)]
     pub in_fractions: bool,
+    /// IP address of the client (for the ledger).
+    #[serde(skip)]
+    pub client_ip: Option<String>,
+    /// Largest amount per request overriding the default (for API keys).
+    #[serde(skip)]
+    pub max_amount: Option<u64>,
 }
 
-/// Processes the airdrop: sends needed transactions into Solana.
-pub async fn airdrop(id: &ReqId, params: Airdrop) -> Result<()> {
-    info!("{} Processing NEON {:?}...", id, params);
+impl Airdrop {
+    /// Returns requested amount in whole tokens (rounded up).
+    pub fn whole_amount(&self) -> Result<u64> {
+        if !self.in_fractions {
+            return Ok(self.amount);
+        }
+        let factor = solana::convert_whole_to_fractions(1)?;
+        let whole = self.amount / factor;
+        if whole * factor < self.amount {
+            Ok(whole + 1)
+        } else {
+            Ok(whole)
+        }
+    }
+}
 
+/// Represents balances of the operator (in whole tokens).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Balance {
+    /// Public key of the operator.
+    pub operator: String,
+    /// Balance of the operator's account.
+    pub sol: f64,
+    /// Balance of the operator's token account.
+    pub neon: f64,
+}
+
+/// Loads NEON parameters from the EVM Loader if not yet loaded.
+pub async fn init(id: &ReqId) -> Result<()> {
     if config::solana_account_seed_version() == 0 {
         config::load_neon_params().await?;
         check_token_account(id).await?;
     }
+    Ok(())
+}
 
+/// Processes the airdrop: sends needed transactions into Solana.
+pub async fn airdrop(id: &ReqId, params: Airdrop) -> Result<solana::Receipt> {
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr as _;
+
+    info!("{} Processing NEON {:?}...", id, params);
+
+    init(id).await?;
+
+    let max_amount = params.max_amount.unwrap_or_else(config::solana_max_amount);
     let limit = if !params.in_fractions {
-        config::solana_max_amount()
+        max_amount
     } else {
-        solana::convert_whole_to_fractions(config::solana_max_amount())?
+        solana::convert_whole_to_fractions(max_amount)?
     };
 
     if params.amount > limit {
-        return Err(eyre!(
-            "Requested value {} exceeds the limit {}",
-            params.amount,
-            limit
-        ));
+        return Err(Error::AmountOverLimit(params.amount, limit).into());
     }
 
-    let operator = config::solana_operator_keypair()
-        .map_err(|e| eyre!("config::solana_operator_keypair: {:?}", e))?;
+    let fractions = if params.in_fractions {
+        params.amount
+    } else {
+        solana::convert_whole_to_fractions(params.amount)?
+    };
+    let mint = Pubkey::from_str(&config::solana_token_mint_id())?;
+    let operator = operators::solana(id, SolanaAsset::Token(mint), fractions).await?;
     let ether_address = ethereum::address_from_str(&params.wallet)
-        .map_err(|e| eyre!("ethereum::address_from_str({}): {:?}", &params.wallet, e))?;
-    solana::deposit_token(
+        .map_err(|_| Error::InvalidAddress(params.wallet.clone()))?;
+
+    let whole_amount = if params.in_fractions {
+        params.amount as f64 / solana::convert_whole_to_fractions(1)? as f64
+    } else {
+        params.amount as f64
+    };
+
+    let key = ledger::open(
+        id,
+        ledger::Entry {
+            token: "NEON".into(),
+            wallet: params.wallet.clone(),
+            amount: params.amount,
+            in_fractions: params.in_fractions,
+            client_ip: params.client_ip.clone(),
+        },
+    )
+    .await;
+
+    let r = solana::deposit_token(
         id,
         operator,
         ether_address,
         params.amount,
         params.in_fractions,
     )
-    .await
-    .map_err(|e| {
-        eyre!(
-            "solana::deposit_token(operator, {}): {:?}",
-            ether_address,
-            e
+    .await;
+
+    match r {
+        Ok(receipt) => {
+            ledger::close(
+                id,
+                key,
+                ledger::Status::Success,
+                Some(receipt.signature.clone()),
+                None,
+            )
+            .await;
+            metrics::airdropped("NEON", whole_amount);
+            Ok(receipt)
+        }
+        Err(e) => {
+            ledger::close(id, key, ledger::Status::Failed, None, Some(e.to_string())).await;
+            if is_program_error(&e) {
+                refresh_params(id.clone());
+            }
+            Err(e.wrap_err(format!(
+                "solana::deposit_token(operator, {})",
+                ether_address
+            )))
+        }
+    }
+}
+
+/// Interval of rechecking the config while the periodic refresh is disabled.
+const REFRESH_IDLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Starts background task re-reading NEON parameters every `solana.neon_params_refresh` seconds.
+pub fn spawn_params_refresh() {
+    actix_web::rt::spawn(async move {
+        loop {
+            let interval = config::solana_neon_params_refresh();
+            if interval == 0 || !config::solana_enabled() {
+                tokio::time::sleep(REFRESH_IDLE_INTERVAL).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+            if let Err(e) = config::load_neon_params().await {
+                error!(
+                    "{} Failed to refresh NEON parameters: {:?}",
+                    id::default(),
+                    e
+                );
+            }
+        }
+    });
+}
+
+/// Shortest interval between refreshes of NEON parameters after program errors.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Flag of a running refresh of NEON parameters after a program error.
+static REFRESHING: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    /// Start time of the last refresh of NEON parameters after a program error.
+    static ref LAST_REFRESH: Mutex<Option<Instant>> = Mutex::new(None);
+}
+
+/// Re-reads NEON parameters in background (they may be outdated after a loader upgrade).
+/// Does nothing if a refresh is running or has started less than `MIN_REFRESH_INTERVAL` ago.
+fn refresh_params(id: ReqId) {
+    if !start_refresh(Instant::now()) {
+        debug!("{} Refresh of NEON parameters is skipped", id);
+        return;
+    }
+    actix_web::rt::spawn(async move {
+        info!("{} Refreshing NEON parameters after program error...", id);
+        if let Err(e) = config::load_neon_params().await {
+            error!("{} Failed to refresh NEON parameters: {:?}", id, e);
+        }
+        REFRESHING.store(false, Ordering::Release);
+    });
+}
+
+/// Marks the refresh as running if it is allowed at the moment.
+fn start_refresh(now: Instant) -> bool {
+    if REFRESHING
+        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        return false;
+    }
+    let mut last = LAST_REFRESH.lock().unwrap();
+    if matches!(*last, Some(t) if now.saturating_duration_since(t) < MIN_REFRESH_INTERVAL) {
+        REFRESHING.store(false, Ordering::Release);
+        return false;
+    }
+    *last = Some(now);
+    true
+}
+
+/// Checks if the error is caused by failed instruction of a transaction.
+fn is_program_error(e: &eyre::Report) -> bool {
+    use solana_client::client_error::ClientError;
+    use solana_sdk::transaction::TransactionError;
+
+    e.chain().any(|cause| {
+        matches!(
+            cause
+                .downcast_ref::<ClientError>()
+                .and_then(|e| e.get_transaction_error()),
+            Some(TransactionError::InstructionError(..))
         )
-    })?;
-    Ok(())
+    })
 }
 
-/// Checks existence and balance of the operator's token account.
-async fn check_token_account(id: &ReqId) -> Result<()> {
-    use eyre::WrapErr as _;
+/// Time to reuse queried balances of the operators.
+const BALANCES_TTL: Duration = Duration::from_secs(30);
+
+lazy_static::lazy_static! {
+    /// Balances of the operators with the time of the query.
+    static ref BALANCES: futures_locks::Mutex<Option<(Instant, Vec<Balance>)>> =
+        futures_locks::Mutex::new(None);
+}
+
+/// Returns balances of the operators' accounts.
+/// The balances are queried at most once per `BALANCES_TTL`, concurrent callers share them.
+pub async fn balances() -> Result<Vec<Balance>> {
+    let mut cache = BALANCES.lock().await;
+    if let Some((time, balances)) = &*cache {
+        if time.elapsed() < BALANCES_TTL {
+            return Ok(balances.clone());
+        }
+    }
+
+    let balances = query_balances().await?;
+    *cache = Some((Instant::now(), balances.clone()));
+    Ok(balances)
+}
+
+/// Queries balances of the operators' accounts.
+async fn query_balances() -> Result<Vec<Balance>> {
+    use solana_sdk::native_token::lamports_to_sol;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Signer as _;
+    use std::str::FromStr as _;
+
+    if config::solana_account_seed_version() == 0 {
+        config::load_neon_params().await?;
+    }
+
+    let token_mint_id = Pubkey::from_str(&config::solana_token_mint_id())?;
+    let mut balances = vec![];
+    for operator in pool::solana_operators().await? {
+        let operator_pubkey = operator.pubkey();
+        let operator_token_pubkey = spl_associated_token_account::get_associated_token_address(
+            &operator_pubkey,
+            &token_mint_id,
+        );
+
+        let (lamports, tokens) = tokio::task::spawn_blocking(move || -> Result<_> {
+            let client = pool::solana_client();
+            let lamports = client.get_balance(&operator_pubkey)?;
+            let tokens = client.get_token_account_balance(&operator_token_pubkey)?;
+            Ok((lamports, tokens))
+        })
+        .await??;
+
+        balances.push(Balance {
+            operator: operator_pubkey.to_string(),
+            sol: lamports_to_sol(lamports),
+            neon: tokens.ui_amount.unwrap_or_default(),
+        });
+    }
+
+    Ok(balances)
+}
+
+/// Checks existence and balance of the operators' token accounts.
+/// Succeeds if at least one operator has tokens.
+pub async fn check_token_account(id: &ReqId) -> Result<()> {
     use solana_account_decoder::parse_token::UiTokenAmount;
     use solana_client::client_error::Result as ClientResult;
-    use solana_client::rpc_client::RpcClient;
     use solana_sdk::pubkey::Pubkey;
     use solana_sdk::signature::Signer as _;
     use std::str::FromStr as _;
 
-    let operator = config::solana_operator_keypair()
-        .map_err(|e| eyre!("config::solana_operator_keypair: {:?}", e))?;
-    let operator_pubkey = operator.pubkey();
-
     let token_mint_id = Pubkey::from_str(&config::solana_token_mint_id()).wrap_err_with(|| {
         eyre!(
             "config::solana_token_mint_id returns {}",
@@ -83,27 +309,46 @@ async fn check_token_account(id: &ReqId) -> Result<()> {
         )
     })?;
 
-    let operator_token_pubkey = spl_associated_token_account::get_associated_token_address(
-        &operator_pubkey,
-        &token_mint_id,
-    );
+    let mut result = Err(Error::InsufficientBalance.into());
+    for operator in pool::solana_operators().await? {
+        let operator_token_pubkey = spl_associated_token_account::get_associated_token_address(
+            &operator.pubkey(),
+            &token_mint_id,
+        );
 
-    info!("{} Token account: {}", id, operator_token_pubkey);
-    let r = tokio::task::spawn_blocking(move || -> ClientResult<UiTokenAmount> {
-        let client =
-            RpcClient::new_with_commitment(config::solana_url(), config::solana_commitment());
-        client.get_token_account_balance(&operator_token_pubkey)
-    })
-    .await??;
-
-    let amount = r.ui_amount.unwrap_or_default();
-    if amount <= f64::default() {
-        return Err(eyre!(
-            "Account {} has zero token balance {}",
-            operator_token_pubkey,
-            amount
-        ));
+        info!("{} Token account: {}", id, operator_token_pubkey);
+        let r = tokio::task::spawn_blocking(move || -> ClientResult<UiTokenAmount> {
+            let client = pool::solana_client();
+            client.get_token_account_balance(&operator_token_pubkey)
+        })
+        .await?;
+
+        match r {
+            Ok(r) if r.ui_amount.unwrap_or_default() > f64::default() => return Ok(()),
+            Ok(r) => error!(
+                "{} Account {} has zero token balance {}",
+                id,
+                operator_token_pubkey,
+                r.ui_amount.unwrap_or_default()
+            ),
+            Err(e) => {
+                error!("{} Account {}: {}", id, operator_token_pubkey, e);
+                result = Err(e.into());
+            }
+        }
     }
 
-    Ok(())
+    result
+}
+
+#[test]
+fn test_start_refresh() {
+    let t0 = Instant::now();
+    assert!(start_refresh(t0));
+    // Running
+    assert!(!start_refresh(t0 + MIN_REFRESH_INTERVAL));
+    REFRESHING.store(false, Ordering::Release);
+    // Too early
+    assert!(!start_refresh(t0 + Duration::from_secs(1)));
+    assert!(start_refresh(t0 + MIN_REFRESH_INTERVAL));
 }
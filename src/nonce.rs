@@ -0,0 +1,344 @@
+//! Faucet nonce manager module.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use eyre::Result;
+use tracing::{debug, info, warn};
+
+use web3::api::Eth;
+use web3::types::{
+    Address, BlockNumber, Bytes, TransactionReceipt, TransactionRequest, H256, U256, U64,
+};
+use web3::Transport;
+
+use crate::id::ReqId;
+use crate::signer::{self, EthereumSigner};
+use crate::{config, gas};
+
+/// Largest number of replacements of a stuck transaction.
+const MAX_REPLACEMENTS: usize = 3;
+
+/// Interval of polling for a transaction receipt.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+lazy_static::lazy_static! {
+    /// Nonces of each sender.
+    static ref NONCES: Mutex<HashMap<Address, Nonces>> = Mutex::new(HashMap::new());
+}
+
+/// Represents nonces of a sender.
+#[derive(Debug, Default)]
+struct Nonces {
+    /// Next nonce to assign.
+    next: U256,
+    /// Assigned nonces of unfinished transactions.
+    pending: BTreeSet<U256>,
+    /// Assigned nonces which are not used (assigned again first to fill the gaps).
+    free: BTreeSet<U256>,
+    /// Flag of reading the nonce from the network once nothing is pending.
+    stale: bool,
+}
+
+/// Represents the nonce manager errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Transaction with nonce {1} from {0:?} is stuck: {2:?}")]
+    Stuck(Address, U256, Vec<H256>),
+
+    #[error("Transaction {0:?} is reverted")]
+    Reverted(H256),
+}
+
+/// Returns the next nonce of the sender, reading the pending transaction count if unknown.
+pub async fn next<T: Transport>(eth: &Eth<T>, from: Address) -> web3::Result<U256> {
+    if let Some(nonce) = assign(from, None) {
+        return Ok(nonce);
+    }
+    let count = eth
+        .transaction_count(from, Some(BlockNumber::Pending))
+        .await?;
+    debug!("Nonce of {:?} is synced to {}", from, count);
+    Ok(assign(from, Some(count)).unwrap_or(count))
+}
+
+/// Finishes the transaction with the nonce (it is mined, failed or left in the mempool).
+pub fn finish(from: Address, nonce: U256) {
+    update(from, |nonces| {
+        nonces.pending.remove(&nonce);
+    });
+}
+
+/// Returns the nonce of a transaction which is not sent (to be assigned again)
+/// and resyncs the nonce of the sender.
+pub fn release(from: Address, nonce: U256) {
+    update(from, |nonces| {
+        nonces.pending.remove(&nonce);
+        nonces.free.insert(nonce);
+        nonces.stale = true;
+    });
+}
+
+/// Forgets the nonce of the sender: it is read from the network on next use
+/// once no other transaction of the sender is pending.
+pub fn resync(from: Address) {
+    update(from, |nonces| nonces.stale = true);
+}
+
+fn update(from: Address, f: impl FnOnce(&mut Nonces)) {
+    let mut senders = NONCES.lock().unwrap();
+    if let Some(nonces) = senders.get_mut(&from) {
+        f(nonces);
+        if nonces.stale && nonces.pending.is_empty() {
+            senders.remove(&from);
+        }
+    }
+}
+
+fn assign(from: Address, count: Option<U256>) -> Option<U256> {
+    let mut senders = NONCES.lock().unwrap();
+    if matches!(senders.get(&from), Some(n) if n.stale && n.pending.is_empty()) {
+        senders.remove(&from);
+    }
+    let nonces = match count {
+        Some(count) => senders.entry(from).or_insert_with(|| Nonces {
+            next: count,
+            ..Default::default()
+        }),
+        None => senders.get_mut(&from)?,
+    };
+    let assigned = match nonces.free.pop_first() {
+        Some(nonce) => nonce,
+        None => {
+            nonces.next += U256::one();
+            nonces.next - U256::one()
+        }
+    };
+    nonces.pending.insert(assigned);
+    Some(assigned)
+}
+
+/// Represents a sent transaction.
+#[derive(Debug)]
+pub struct Sent {
+    pub hash: H256,
+    /// Number of the block with the transaction (if mined).
+    pub block: Option<U64>,
+    /// Flag of reaching `web3.confirmations` before `web3.confirmation_timeout`.
+    pub confirmed: bool,
+}
+
+/// Signs and sends a transaction with a locally assigned nonce and waits for
+/// `web3.confirmations` blocks on top of it.
+/// A transaction pending longer than `web3.replace_timeout` is replaced by one with the
+/// same nonce and higher fees.
+pub async fn send<T: Transport>(
+    id: &ReqId,
+    eth: Eth<T>,
+    signer: &EthereumSigner,
+    to: Address,
+    data: Bytes,
+    gas: U256,
+) -> Result<Sent> {
+    let from = signer.address();
+    let nonce = next(&eth, from).await?;
+    let r = send_with(id, &eth, signer, to, data, gas, nonce).await;
+    finish(from, nonce);
+    r
+}
+
+async fn send_with<T: Transport>(
+    id: &ReqId,
+    eth: &Eth<T>,
+    signer: &EthereumSigner,
+    to: Address,
+    data: Bytes,
+    gas: U256,
+    nonce: U256,
+) -> Result<Sent> {
+    let from = signer.address();
+    let mut fees = match gas::fees(eth).await {
+        Ok(fees) => fees,
+        Err(e) => {
+            release(from, nonce);
+            return Err(e.into());
+        }
+    };
+    let replace_timeout = Duration::from_secs(config::web3_replace_timeout());
+    let deadline = Instant::now() + Duration::from_secs(config::web3_confirmation_timeout());
+
+    let mut hashes = vec![];
+    let mut replacements = 0;
+    let receipt = loop {
+        let mut tx = TransactionRequest {
+            from,
+            to: Some(to),
+            gas: Some(gas),
+            nonce: Some(nonce),
+            data: Some(data.clone()),
+            ..Default::default()
+        };
+        fees.apply(&mut tx);
+        let sent = match signer::sign_transaction(eth, signer, tx).await {
+            Ok(raw) => eth.send_raw_transaction(raw).await,
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(hash) => {
+                info!("{} Sent transaction {:?} with nonce {}", id, hash, nonce);
+                hashes.push(hash);
+            }
+            Err(e) if hashes.is_empty() => {
+                release(from, nonce);
+                return Err(e.into());
+            }
+            // The previous transaction may be mined meanwhile
+            Err(e) => warn!("{} Failed replacement: {}", id, e),
+        }
+
+        let replace_at = (!replace_timeout.is_zero()).then(|| Instant::now() + replace_timeout);
+        match wait(eth, &hashes, replace_at, deadline).await? {
+            Wait::Mined(receipt) => break *receipt,
+            Wait::Deadline => {
+                warn!("{} Transaction with nonce {} is not mined yet", id, nonce);
+                return Ok(Sent {
+                    hash: *hashes.last().expect("sent transaction"),
+                    block: None,
+                    confirmed: false,
+                });
+            }
+            Wait::Replace => match fees.bump() {
+                Some(bumped) if replacements < MAX_REPLACEMENTS => {
+                    replacements += 1;
+                    fees = bumped;
+                    warn!(
+                        "{} Replacing transaction with nonce {} ({:?})",
+                        id, nonce, fees
+                    );
+                }
+                _ => {
+                    resync(from);
+                    return Err(Error::Stuck(from, nonce, hashes).into());
+                }
+            },
+        }
+    };
+
+    if receipt.status == Some(U64::zero()) {
+        return Err(Error::Reverted(receipt.transaction_hash).into());
+    }
+    confirm(id, eth, receipt, config::web3_confirmations(), deadline).await
+}
+
+enum Wait {
+    Mined(Box<TransactionReceipt>),
+    Replace,
+    Deadline,
+}
+
+/// Polls receipts of transactions (sharing the same nonce) until one is mined,
+/// it is time to replace the transaction or the deadline expires.
+async fn wait<T: Transport>(
+    eth: &Eth<T>,
+    hashes: &[H256],
+    replace_at: Option<Instant>,
+    deadline: Instant,
+) -> web3::Result<Wait> {
+    loop {
+        for hash in hashes {
+            if let Some(receipt) = eth.transaction_receipt(*hash).await? {
+                if receipt.block_number.is_some() {
+                    return Ok(Wait::Mined(Box::new(receipt)));
+                }
+            }
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(Wait::Deadline);
+        }
+        if matches!(replace_at, Some(r) if now >= r) {
+            return Ok(Wait::Replace);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Waits until the transaction has the number of blocks on top of it
+/// (following the receipt if the transaction is moved by a reorg).
+async fn confirm<T: Transport>(
+    id: &ReqId,
+    eth: &Eth<T>,
+    mut receipt: TransactionReceipt,
+    confirmations: u64,
+    deadline: Instant,
+) -> Result<Sent> {
+    loop {
+        if let Some(block) = receipt.block_number {
+            let last = eth.block_number().await?;
+            if last >= block + confirmations {
+                let current = eth.transaction_receipt(receipt.transaction_hash).await?;
+                match current {
+                    Some(current) if current.block_hash == receipt.block_hash => {
+                        debug!(
+                            "{} Transaction {:?} has {} confirmations",
+                            id,
+                            receipt.transaction_hash,
+                            last - block
+                        );
+                        return Ok(Sent {
+                            hash: receipt.transaction_hash,
+                            block: Some(block),
+                            confirmed: true,
+                        });
+                    }
+                    Some(current) => receipt = current,
+                    None => receipt.block_number = None,
+                }
+                continue;
+            }
+        } else if let Some(current) = eth.transaction_receipt(receipt.transaction_hash).await? {
+            receipt = current;
+        }
+
+        if Instant::now() >= deadline {
+            warn!(
+                "{} Transaction {:?} is not confirmed yet",
+                id, receipt.transaction_hash
+            );
+            return Ok(Sent {
+                hash: receipt.transaction_hash,
+                block: receipt.block_number,
+                confirmed: false,
+            });
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[test]
+fn test_assign() {
+    let from = Address::from_low_u64_be(0xface);
+    assert_eq!(assign(from, None), None);
+    assert_eq!(assign(from, Some(5.into())), Some(5.into()));
+    assert_eq!(assign(from, Some(1.into())), Some(6.into()));
+    assert_eq!(assign(from, None), Some(7.into()));
+
+    // Unused nonce is assigned again while others are pending
+    release(from, 6.into());
+    assert_eq!(assign(from, None), Some(6.into()));
+    assert_eq!(assign(from, None), Some(8.into()));
+    for nonce in [5, 6, 7] {
+        finish(from, nonce.into());
+    }
+    assert_eq!(assign(from, None), Some(9.into()));
+
+    // Resync waits for pending nonces
+    resync(from);
+    finish(from, 8.into());
+    assert_eq!(assign(from, None), Some(10.into()));
+    finish(from, 9.into());
+    finish(from, 10.into());
+    assert_eq!(assign(from, None), None);
+    assert_eq!(assign(from, Some(2.into())), Some(2.into()));
+}
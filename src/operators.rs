@@ -0,0 +1,280 @@
+//! Faucet operators selection module.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use eyre::{eyre, Result};
+use tracing::{info, warn};
+
+use solana_sdk::native_token::lamports_to_sol;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer as _;
+use spl_associated_token_account::get_associated_token_address;
+use web3::types::{Address, U256};
+
+use crate::config::{self, SELECTION_BALANCE};
+use crate::error::Error;
+use crate::id::ReqId;
+use crate::signer::{EthereumSigner, SolanaSigner};
+use crate::{erc20_tokens, pool};
+
+/// Index of the next operator for round-robin selection.
+static NEXT_SOLANA: AtomicUsize = AtomicUsize::new(0);
+static NEXT_WEB3: AtomicUsize = AtomicUsize::new(0);
+
+/// Represents balance of an operator (in SOL or in native tokens of Web3).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Balance {
+    pub operator: String,
+    pub balance: f64,
+}
+
+/// Represents an asset airdropped by a Solana operator.
+#[derive(Debug, Clone, Copy)]
+pub enum SolanaAsset {
+    /// Lamports of the operator's account.
+    Sol,
+    /// Tokens of the mint in the operator's associated token account.
+    Token(Pubkey),
+}
+
+/// Chooses the Solana operator for an airdrop of `amount` (in lamports or token fractions).
+pub async fn solana(id: &ReqId, asset: SolanaAsset, amount: u64) -> Result<Arc<SolanaSigner>> {
+    let operators = pool::solana_operators().await?;
+    let min_balance = config::solana_operator_min_balance();
+    if operators.len() == 1 && min_balance <= 0.0 {
+        return Ok(operators[0].clone());
+    }
+    select(
+        id,
+        operators,
+        |o| o.pubkey().to_string(),
+        |o| solana_coverage(o.clone(), asset, amount, min_balance),
+        &config::solana_operator_selection(),
+        &NEXT_SOLANA,
+    )
+    .await
+}
+
+/// Chooses the Ethereum operator for an airdrop of tokens (amounts in fractions).
+/// Tokens minted by the operator are not expected in `tokens`.
+pub async fn web3(id: &ReqId, tokens: &[(Address, U256)]) -> Result<Arc<EthereumSigner>> {
+    let operators = pool::web3_operators().await?;
+    let min_balance = config::web3_operator_min_balance();
+    if operators.len() == 1 && min_balance <= 0.0 {
+        return Ok(operators[0].clone());
+    }
+    select(
+        id,
+        operators,
+        |o| format!("{:?}", o.address()),
+        |o| web3_coverage(o.clone(), tokens, min_balance),
+        &config::web3_operator_selection(),
+        &NEXT_WEB3,
+    )
+    .await
+}
+
+/// Returns SOL balances of all Solana operators.
+pub async fn solana_balances() -> Result<Vec<Balance>> {
+    let mut balances = vec![];
+    for operator in pool::solana_operators().await? {
+        balances.push(Balance {
+            operator: operator.pubkey().to_string(),
+            balance: solana_balance(operator).await?,
+        });
+    }
+    Ok(balances)
+}
+
+/// Returns native balances of all Ethereum operators.
+pub async fn web3_balances() -> Result<Vec<Balance>> {
+    let mut balances = vec![];
+    for operator in pool::web3_operators().await? {
+        balances.push(Balance {
+            operator: format!("{:?}", operator.address()),
+            balance: web3_balance(operator).await?,
+        });
+    }
+    Ok(balances)
+}
+
+/// Chooses an operator by round-robin or by the highest coverage (number of
+/// such airdrops the operator can afford), skipping operators failing the check.
+async fn select<T, N, C, F>(
+    id: &ReqId,
+    operators: Vec<T>,
+    name: N,
+    coverage: C,
+    selection: &str,
+    next: &AtomicUsize,
+) -> Result<T>
+where
+    T: Clone,
+    N: Fn(&T) -> String,
+    C: Fn(&T) -> F,
+    F: Future<Output = Result<f64>>,
+{
+    if selection == SELECTION_BALANCE {
+        let mut best: Option<(&T, f64)> = None;
+        for operator in &operators {
+            match coverage(operator).await {
+                Ok(c) if !matches!(best, Some((_, max)) if c <= max) => best = Some((operator, c)),
+                Ok(c) => info!(
+                    "{} Operator {} covers {:.1} airdrops",
+                    id,
+                    name(operator),
+                    c
+                ),
+                Err(e) => warn!("{} Skipped operator {}: {:#}", id, name(operator), e),
+            }
+        }
+        if let Some((operator, c)) = best {
+            info!(
+                "{} Operator {} (covers {:.1} airdrops)",
+                id,
+                name(operator),
+                c
+            );
+            return Ok(operator.clone());
+        }
+    } else {
+        let start = next.fetch_add(1, Ordering::Relaxed);
+        for i in 0..operators.len() {
+            let operator = &operators[(start + i) % operators.len()];
+            match coverage(operator).await {
+                Ok(c) => {
+                    info!(
+                        "{} Operator {} (covers {:.1} airdrops)",
+                        id,
+                        name(operator),
+                        c
+                    );
+                    return Ok(operator.clone());
+                }
+                Err(e) => warn!("{} Skipped operator {}: {:#}", id, name(operator), e),
+            }
+        }
+    }
+
+    Err(Error::InsufficientBalance.into())
+}
+
+/// Returns how many airdrops of `amount` the Solana operator can afford.
+/// Fails if the SOL balance is below `min_balance` or the asset balance is below `amount`.
+async fn solana_coverage(
+    operator: Arc<SolanaSigner>,
+    asset: SolanaAsset,
+    amount: u64,
+    min_balance: f64,
+) -> Result<f64> {
+    let pubkey = operator.pubkey();
+    let (lamports, tokens) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let client = pool::solana_client();
+        let lamports = client.get_balance(&pubkey)?;
+        let tokens = match asset {
+            SolanaAsset::Sol => None,
+            SolanaAsset::Token(mint) => {
+                let account = get_associated_token_address(&pubkey, &mint);
+                let balance = client.get_token_account_balance(&account)?;
+                Some(balance.amount.parse::<u64>()?)
+            }
+        };
+        Ok((lamports, tokens))
+    })
+    .await??;
+
+    let sol = lamports_to_sol(lamports);
+    if sol < min_balance {
+        return Err(eyre!("SOL balance {} is below {}", sol, min_balance));
+    }
+    coverage(tokens.unwrap_or(lamports).into(), amount.into())
+}
+
+/// Returns how many airdrops of the tokens the Ethereum operator can afford.
+/// Fails if the native balance is below `min_balance` or a token balance is below its amount.
+async fn web3_coverage(
+    operator: Arc<EthereumSigner>,
+    tokens: &[(Address, U256)],
+    min_balance: f64,
+) -> Result<f64> {
+    let eth = pool::web3()?.eth();
+    let native = wei_to_f64(eth.balance(operator.address(), None).await?);
+    if native < min_balance {
+        return Err(eyre!("Native balance {} is below {}", native, min_balance));
+    }
+    let mut min = f64::MAX;
+    for (token, amount) in tokens {
+        let balance = erc20_tokens::balance_of(eth.clone(), *token, operator.address()).await?;
+        let c = coverage(balance, *amount).map_err(|e| eyre!("Token {:?}: {}", token, e))?;
+        min = min.min(c);
+    }
+    Ok(if tokens.is_empty() { native } else { min })
+}
+
+/// Returns the number of amounts covered by the balance (fails if less than one).
+fn coverage(balance: U256, amount: U256) -> Result<f64> {
+    if balance < amount {
+        return Err(eyre!("Balance {} is below requested {}", balance, amount));
+    }
+    Ok(u256_to_f64(balance) / u256_to_f64(amount.max(U256::one())))
+}
+
+async fn solana_balance(operator: Arc<SolanaSigner>) -> Result<f64> {
+    let pubkey = operator.pubkey();
+    let lamports = tokio::task::spawn_blocking(move || -> Result<u64> {
+        Ok(pool::solana_client().get_balance(&pubkey)?)
+    })
+    .await??;
+    Ok(lamports_to_sol(lamports))
+}
+
+async fn web3_balance(operator: Arc<EthereumSigner>) -> Result<f64> {
+    let wei = pool::web3()?
+        .eth()
+        .balance(operator.address(), None)
+        .await?;
+    Ok(wei_to_f64(wei))
+}
+
+/// Converts wei to whole native tokens (10^18 wei).
+fn wei_to_f64(wei: U256) -> f64 {
+    u256_to_f64(wei) / 1e18
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse::<f64>().unwrap_or_default()
+}
+
+#[test]
+fn test_select() {
+    let id = crate::id::default();
+    let balances = [50u64, 200, 100];
+    let covers = |amount: u64| {
+        move |o: &usize| {
+            let c = coverage(balances[*o].into(), amount.into());
+            async move { c }
+        }
+    };
+    let name = |o: &usize| o.to_string();
+    let next = AtomicUsize::new(0);
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let select =
+            |selection, amount| select(&id, vec![0, 1, 2], name, covers(amount), selection, &next);
+        assert_eq!(select("round_robin", 10).await.unwrap(), 0);
+        assert_eq!(select("round_robin", 10).await.unwrap(), 1);
+        assert_eq!(select("round_robin", 10).await.unwrap(), 2);
+        assert_eq!(select("round_robin", 100).await.unwrap(), 1); // skips 0
+        assert_eq!(select("round_robin", 100).await.unwrap(), 1);
+        assert_eq!(select("round_robin", 100).await.unwrap(), 2);
+        assert_eq!(select(SELECTION_BALANCE, 10).await.unwrap(), 1);
+        assert!(select(SELECTION_BALANCE, 300).await.is_err());
+    });
+    assert_eq!(coverage(100.into(), 0.into()).unwrap(), 100.0);
+}
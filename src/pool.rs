@@ -0,0 +1,122 @@
+//! Faucet pool of shared clients and signers.
+
+use std::sync::{Arc, RwLock};
+
+use eyre::Result;
+use tracing::debug;
+
+use solana_client::rpc_client::RpcClient;
+use web3::api::Eth;
+use web3::transports::Http;
+use web3::{Transport, Web3};
+
+use crate::config;
+use crate::signer::{self, EthereumSigner, SolanaSigner};
+
+lazy_static::lazy_static! {
+    static ref POOL: RwLock<Pool> = RwLock::new(Pool::default());
+    /// Lock of creating operators (signers are created once by concurrent callers).
+    static ref CREATING: futures_locks::Mutex<()> = futures_locks::Mutex::new(());
+}
+
+/// Represents lazily initialized clients and signers.
+///
+/// The pool is shared by all workers rather than kept per worker: the Solana client is
+/// used from blocking threads, nonces and operator selection need the same signers in
+/// every worker, and a reload must drop the clients of all workers at once.
+/// The lock is held only to clone an `Arc` (write lock only on creation).
+#[derive(Default)]
+struct Pool {
+    solana_client: Option<Arc<RpcClient>>,
+    solana_operators: Option<Vec<Arc<SolanaSigner>>>,
+    web3: Option<Web3<Http>>,
+    web3_operators: Option<Vec<Arc<EthereumSigner>>>,
+    web3_chain_id: Option<u64>,
+}
+
+/// Returns the Solana RPC client. Must be called in blocking context.
+pub fn solana_client() -> Arc<RpcClient> {
+    if let Some(client) = &POOL.read().unwrap().solana_client {
+        return client.clone();
+    }
+    let mut pool = POOL.write().unwrap();
+    pool.solana_client
+        .get_or_insert_with(|| {
+            debug!("Creating Solana RPC client...");
+            Arc::new(RpcClient::new_with_commitment(
+                config::solana_url(),
+                config::solana_commitment(),
+            ))
+        })
+        .clone()
+}
+
+/// Returns the Solana operators.
+/// The signers are created (reading keypairs, decrypting keystores) on a blocking thread.
+pub async fn solana_operators() -> Result<Vec<Arc<SolanaSigner>>> {
+    if let Some(operators) = &POOL.read().unwrap().solana_operators {
+        return Ok(operators.clone());
+    }
+    let _creating = CREATING.lock().await;
+    if let Some(operators) = &POOL.read().unwrap().solana_operators {
+        return Ok(operators.clone());
+    }
+    debug!("Creating Solana operators...");
+    let operators = tokio::task::spawn_blocking(signer::solana_operators)
+        .await??
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<_>>();
+    POOL.write().unwrap().solana_operators = Some(operators.clone());
+    Ok(operators)
+}
+
+/// Returns the Web3 client.
+pub fn web3() -> Result<Web3<Http>> {
+    if let Some(web3) = &POOL.read().unwrap().web3 {
+        return Ok(web3.clone());
+    }
+    debug!("Creating Web3 client...");
+    let web3 = Web3::new(Http::new(&config::web3_rpc_url())?);
+    POOL.write().unwrap().web3 = Some(web3.clone());
+    Ok(web3)
+}
+
+/// Returns the Ethereum operators.
+/// The signers are created (decrypting keystores) on a blocking thread.
+pub async fn web3_operators() -> Result<Vec<Arc<EthereumSigner>>> {
+    if let Some(operators) = &POOL.read().unwrap().web3_operators {
+        return Ok(operators.clone());
+    }
+    let _creating = CREATING.lock().await;
+    if let Some(operators) = &POOL.read().unwrap().web3_operators {
+        return Ok(operators.clone());
+    }
+    debug!("Creating Ethereum operators...");
+    let operators = tokio::task::spawn_blocking(signer::web3_operators)
+        .await??
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<_>>();
+    POOL.write().unwrap().web3_operators = Some(operators.clone());
+    Ok(operators)
+}
+
+/// Returns the chain id of the Web3 network (read once).
+pub async fn web3_chain_id<T: Transport>(eth: &Eth<T>) -> web3::Result<u64> {
+    if let Some(chain_id) = POOL.read().unwrap().web3_chain_id {
+        return Ok(chain_id);
+    }
+    let chain_id = eth.chain_id().await?.as_u64();
+    debug!("Chain id of Web3 network is {}", chain_id);
+    POOL.write().unwrap().web3_chain_id = Some(chain_id);
+    Ok(chain_id)
+}
+
+/// Drops all clients and signers (they are recreated from the current config on demand).
+pub fn invalidate() {
+    debug!("Invalidating pool of clients and signers...");
+    let old = std::mem::take(&mut *POOL.write().unwrap());
+    // Solana RPC client must not be dropped in async context
+    tokio::task::spawn_blocking(move || drop(old));
+}
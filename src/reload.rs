@@ -0,0 +1,67 @@
+//! Faucet config reload module.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use eyre::Result;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, info};
+
+use crate::{config, id, pool};
+
+/// Interval of checking modification time of the config file.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Starts background tasks reloading the config on SIGHUP
+/// and, if `rpc.watch_config` is enabled, on change of the file.
+pub fn spawn(file: &Path) -> Result<()> {
+    let mut hangup = signal(SignalKind::hangup())?;
+    let f = file.to_owned();
+    actix_web::rt::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("{} Received SIGHUP, reloading config...", id::default());
+            reload(&f);
+        }
+    });
+
+    if config::rpc_watch_config() {
+        let file = file.to_owned();
+        actix_web::rt::spawn(async move { watch(file).await });
+    }
+
+    Ok(())
+}
+
+/// Polls modification time of the file and reloads the config on change.
+async fn watch(file: PathBuf) {
+    let mut last = modified(&file);
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let current = modified(&file);
+        if current != last {
+            last = current;
+            info!(
+                "{} File {:?} is changed, reloading config...",
+                id::default(),
+                file
+            );
+            reload(&file);
+        }
+    }
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file).and_then(|m| m.modified()).ok()
+}
+
+fn reload(file: &Path) {
+    match config::reload(file) {
+        Ok(()) => pool::invalidate(),
+        Err(e) => error!(
+            "{} Rejected new config (keeping current): {}",
+            id::default(),
+            e
+        ),
+    }
+}
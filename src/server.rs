@@ -1,13 +1,26 @@
 //! Faucet server implementation.
 
 use actix_cors::Cors;
+use actix_web::dev::Service as _;
 use actix_web::http::{header, StatusCode};
-use actix_web::web::{get, post, Bytes};
-use actix_web::{App, HttpResponse, HttpServer, Responder};
+use actix_web::web::{get, post, Bytes, Path};
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer, Responder};
 use eyre::Result;
+use std::future::Future;
+use std::time::Instant;
 use tracing::{error, info};
 
-use crate::{active_requests, config, erc20_tokens, id, neon_token};
+use crate::error::Error;
+use crate::{
+    active_requests, auth, captcha, config, erc20_tokens, health, id, jobs, limits, metrics,
+    neon_token, sol_token, spl_tokens,
+};
+
+/// Header with the captcha token of the client.
+const CAPTCHA_TOKEN: &str = "X-Captcha-Token";
+
+/// Header with addresses of the client and proxies.
+const X_FORWARDED_FOR: &str = "X-Forwarded-For";
 
 /// Starts the server in listening mode.
 pub async fn start(workers: usize) -> Result<()> {
@@ -16,20 +29,35 @@ pub async fn start(workers: usize) -> Result<()> {
     info!("{} Bind {}:{}", id::default(), rpc_bind, rpc_port);
 
     HttpServer::new(|| {
-        let mut cors = Cors::default();
-        let allowed_origins = config::allowed_origins();
-        if !allowed_origins.is_empty() {
-            cors = cors
-                .allowed_methods(vec!["GET", "POST"])
-                .allowed_header(header::CONTENT_TYPE)
-                .max_age(3600);
-            for origin in &allowed_origins {
-                cors = cors.allowed_origin(origin);
-            }
-        }
+        // Origins are checked on each request to follow reloads of the config
+        let cors = Cors::default()
+            .allowed_origin_fn(|origin, _| {
+                config::allowed_origins()
+                    .iter()
+                    .any(|o| o.as_bytes() == origin.as_bytes())
+            })
+            .allowed_methods(vec!["GET", "POST"])
+            .allowed_header(header::CONTENT_TYPE)
+            .allowed_header(header::AUTHORIZATION)
+            .allowed_header(CAPTCHA_TOKEN)
+            .max_age(3600);
 
         App::new()
             .wrap(cors)
+            .wrap_fn(|req, srv| {
+                let endpoint = req.match_pattern().unwrap_or_else(|| "unmatched".into());
+                let started = Instant::now();
+                let response = srv.call(req);
+                async move {
+                    let response = response.await?;
+                    let status = response.status().as_u16();
+                    metrics::observe_request(&endpoint, status, started.elapsed());
+                    Ok(response)
+                }
+            })
+            .route("/health", get().to(handle_health))
+            .route("/ready", get().to(handle_ready))
+            .route("/metrics", get().to(handle_metrics))
             .route("/request_ping", get().to(handle_request_ping))
             .route("/request_version", get().to(handle_request_version))
             .route(
@@ -38,7 +66,15 @@ pub async fn start(workers: usize) -> Result<()> {
             )
             .route("/request_neon", post().to(handle_request_neon))
             .route("/request_erc20_list", get().to(handle_request_erc20_list))
+            .route(
+                "/v1/request_erc20_list",
+                get().to(handle_request_erc20_list_v1),
+            )
             .route("/request_erc20", post().to(handle_request_erc20))
+            .route("/request_sol", post().to(handle_request_sol))
+            .route("/request_spl_list", get().to(handle_request_spl_list))
+            .route("/request_spl", post().to(handle_request_spl))
+            .route("/request_status/{id}", get().to(handle_request_status))
     })
     .bind((rpc_bind, rpc_port))?
     .workers(workers)
@@ -60,7 +96,7 @@ async fn handle_request_ping(body: Bytes) -> impl Responder {
     let input = String::from_utf8(body.to_vec());
     if let Err(err) = input {
         error!("{} BadRequest (body): {}", id, err);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadBody(err.to_string()));
     }
 
     let ping = input.unwrap();
@@ -85,7 +121,7 @@ async fn handle_request_version() -> impl Responder {
 }
 
 /// Handles a request for NEON airdrop in galans (1 galan = 10E-9 NEON).
-async fn handle_request_neon_in_galans(body: Bytes) -> impl Responder {
+async fn handle_request_neon_in_galans(req: HttpRequest, body: Bytes) -> impl Responder {
     let id = id::generate();
     let counter = active_requests::increment();
 
@@ -93,31 +129,74 @@ async fn handle_request_neon_in_galans(body: Bytes) -> impl Responder {
     info!("{} Handling request for NEON (in galans) Airdrop...", id);
     info!("{} Active requests: {}", id, counter);
 
+    let caller = auth::authorize(&id, "request_neon_in_galans", authorization(&req));
+    if let Err(err) = caller {
+        error!("{} Unauthorized: {}", id, err);
+        return error_response(&id, err.into());
+    }
+    let caller = caller.unwrap();
+
     let input = String::from_utf8(body.to_vec());
     if let Err(err) = input {
         error!("{} BadRequest (body): {}", id, err);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadBody(err.to_string()));
     }
 
     let input = input.unwrap();
     let airdrop = serde_json::from_str::<neon_token::Airdrop>(&input);
     if let Err(err) = airdrop {
         error!("{} BadRequest (json): {} in '{}'", id, err, input);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadJson(err.to_string()));
     }
 
     let mut airdrop = airdrop.unwrap();
     airdrop.in_fractions = true;
-    if let Err(err) = neon_token::airdrop(&id, airdrop).await {
-        error!("{} InternalServerError: {}", id, err);
-        return HttpResponse::with_body(StatusCode::INTERNAL_SERVER_ERROR, err.to_string());
+    if let Err(err) = neon_token::init(&id).await {
+        error!("{} Failed: {:#}", id, err);
+        return error_response(&id, Error::from_report(err));
     }
 
-    HttpResponse::with_body(StatusCode::OK, String::default())
+    let amount = airdrop.whole_amount();
+    if let Err(err) = amount {
+        error!("{} Failed (amount): {:#}", id, err);
+        return error_response(&id, Error::from_report(err));
+    }
+
+    airdrop.client_ip = client_ip(&req);
+    airdrop.max_amount = caller.max_amount();
+    let admission = admit(
+        &id,
+        &req,
+        &caller,
+        "request_neon_in_galans",
+        limits::Token::Neon,
+        &airdrop.wallet,
+        amount.unwrap(),
+    )
+    .await;
+    if let Err(err) = admission {
+        return error_response(&id, err);
+    }
+    let reservation = admission.unwrap();
+
+    if config::rpc_async_airdrop() {
+        return spawn_job(id, move |id| {
+            let airdrop = async move { neon_token::airdrop(&id, airdrop).await };
+            release_on_failure(airdrop, reservation)
+        });
+    }
+
+    match release_on_failure(neon_token::airdrop(&id, airdrop), reservation).await {
+        Ok(receipt) => json_response(StatusCode::OK, &receipt),
+        Err(err) => {
+            error!("{} Failed: {:#}", id, err);
+            error_response(&id, Error::from_report(err))
+        }
+    }
 }
 
 /// Handles a request for NEON airdrop.
-async fn handle_request_neon(body: Bytes) -> impl Responder {
+async fn handle_request_neon(req: HttpRequest, body: Bytes) -> impl Responder {
     let id = id::generate();
     let counter = active_requests::increment();
 
@@ -125,25 +204,58 @@ async fn handle_request_neon(body: Bytes) -> impl Responder {
     info!("{} Handling request for NEON Airdrop...", id);
     info!("{} Active requests: {}", id, counter);
 
+    let caller = auth::authorize(&id, "request_neon", authorization(&req));
+    if let Err(err) = caller {
+        error!("{} Unauthorized: {}", id, err);
+        return error_response(&id, err.into());
+    }
+    let caller = caller.unwrap();
+
     let input = String::from_utf8(body.to_vec());
     if let Err(err) = input {
         error!("{} BadRequest (body): {}", id, err);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadBody(err.to_string()));
     }
 
     let input = input.unwrap();
     let airdrop = serde_json::from_str::<neon_token::Airdrop>(&input);
     if let Err(err) = airdrop {
         error!("{} BadRequest (json): {} in '{}'", id, err, input);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadJson(err.to_string()));
+    }
+
+    let mut airdrop = airdrop.unwrap();
+    airdrop.client_ip = client_ip(&req);
+    airdrop.max_amount = caller.max_amount();
+    let admission = admit(
+        &id,
+        &req,
+        &caller,
+        "request_neon",
+        limits::Token::Neon,
+        &airdrop.wallet,
+        airdrop.amount,
+    )
+    .await;
+    if let Err(err) = admission {
+        return error_response(&id, err);
     }
+    let reservation = admission.unwrap();
 
-    if let Err(err) = neon_token::airdrop(&id, airdrop.unwrap()).await {
-        error!("{} InternalServerError: {}", id, err);
-        return HttpResponse::with_body(StatusCode::INTERNAL_SERVER_ERROR, err.to_string());
+    if config::rpc_async_airdrop() {
+        return spawn_job(id, move |id| {
+            let airdrop = async move { neon_token::airdrop(&id, airdrop).await };
+            release_on_failure(airdrop, reservation)
+        });
     }
 
-    HttpResponse::with_body(StatusCode::OK, String::default())
+    match release_on_failure(neon_token::airdrop(&id, airdrop), reservation).await {
+        Ok(receipt) => json_response(StatusCode::OK, &receipt),
+        Err(err) => {
+            error!("{} Failed: {:#}", id, err);
+            error_response(&id, Error::from_report(err))
+        }
+    }
 }
 
 /// Handles a request for list of available ERC20 tokens.
@@ -155,23 +267,29 @@ async fn handle_request_erc20_list() -> impl Responder {
     info!("{} Handling request for list of ERC20...", id);
     info!("{} Active requests: {}", id, counter);
 
-    let mut list = String::from("[");
-    for t in config::tokens() {
-        list.push('"');
-        list.push_str(&t);
-        list.push('"');
-        list.push(',');
-    }
-    if list.ends_with(',') {
-        list.pop();
-    }
-    list.push(']');
+    json_response(StatusCode::OK, &config::tokens())
+}
+
+/// Handles a request for list of ERC20 tokens with metadata and balances.
+async fn handle_request_erc20_list_v1() -> impl Responder {
+    let id = id::generate();
+    let counter = active_requests::increment();
 
-    list
+    println!();
+    info!("{} Handling request for list of ERC20 (v1)...", id);
+    info!("{} Active requests: {}", id, counter);
+
+    match erc20_tokens::list(&id).await {
+        Ok(tokens) => json_response(StatusCode::OK, &serde_json::json!({ "tokens": tokens })),
+        Err(err) => {
+            error!("{} Failed: {:#}", id, err);
+            error_response(&id, Error::from_report(err))
+        }
+    }
 }
 
 /// Handles a request for ERC20 tokens airdrop.
-async fn handle_request_erc20(body: Bytes) -> impl Responder {
+async fn handle_request_erc20(req: HttpRequest, body: Bytes) -> impl Responder {
     let id = id::generate();
     let counter = active_requests::increment();
 
@@ -179,25 +297,411 @@ async fn handle_request_erc20(body: Bytes) -> impl Responder {
     info!("{} Handling request for ERC20 Airdrop...", id);
     info!("{} Active requests: {}", id, counter);
 
+    let caller = auth::authorize(&id, "request_erc20", authorization(&req));
+    if let Err(err) = caller {
+        error!("{} Unauthorized: {}", id, err);
+        return error_response(&id, err.into());
+    }
+    let caller = caller.unwrap();
+
     let input = String::from_utf8(body.to_vec());
     if let Err(err) = input {
         error!("{} BadRequest (body): {}", id, err);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadBody(err.to_string()));
     }
 
     let input = input.unwrap();
     let airdrop = serde_json::from_str::<erc20_tokens::Airdrop>(&input);
     if let Err(err) = airdrop {
         error!("{} BadRequest (json): {} in '{}'", id, err, input);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadJson(err.to_string()));
+    }
+
+    let mut airdrop = airdrop.unwrap();
+    if let Err(err) = airdrop.resolve() {
+        error!("{} BadRequest (token): {}", id, err);
+        return error_response(&id, err);
+    }
+    airdrop.client_ip = client_ip(&req);
+    airdrop.max_amount = caller.max_amount();
+    let admission = admit(
+        &id,
+        &req,
+        &caller,
+        "request_erc20",
+        limits::Token::Erc20,
+        &airdrop.wallet,
+        airdrop.requested_amount(),
+    )
+    .await;
+    if let Err(err) = admission {
+        return error_response(&id, err);
+    }
+    let reservation = admission.unwrap();
+
+    if config::rpc_async_airdrop() {
+        return spawn_job(id, move |id| {
+            let airdrop = async move { erc20_tokens::airdrop(&id, airdrop).await };
+            release_on_failure(airdrop, reservation)
+        });
+    }
+
+    match release_on_failure(erc20_tokens::airdrop(&id, airdrop), reservation).await {
+        Ok(receipt) => json_response(StatusCode::OK, &receipt),
+        Err(err) => {
+            error!("{} Failed: {:#}", id, err);
+            error_response(&id, Error::from_report(err))
+        }
+    }
+}
+
+/// Handles a request for SOL airdrop.
+async fn handle_request_sol(req: HttpRequest, body: Bytes) -> impl Responder {
+    let id = id::generate();
+    let counter = active_requests::increment();
+
+    println!();
+    info!("{} Handling request for SOL Airdrop...", id);
+    info!("{} Active requests: {}", id, counter);
+
+    let caller = auth::authorize(&id, "request_sol", authorization(&req));
+    if let Err(err) = caller {
+        error!("{} Unauthorized: {}", id, err);
+        return error_response(&id, err.into());
+    }
+    let caller = caller.unwrap();
+
+    let input = String::from_utf8(body.to_vec());
+    if let Err(err) = input {
+        error!("{} BadRequest (body): {}", id, err);
+        return error_response(&id, Error::BadBody(err.to_string()));
+    }
+
+    let input = input.unwrap();
+    let airdrop = serde_json::from_str::<sol_token::Airdrop>(&input);
+    if let Err(err) = airdrop {
+        error!("{} BadRequest (json): {} in '{}'", id, err, input);
+        return error_response(&id, Error::BadJson(err.to_string()));
     }
 
-    if let Err(err) = erc20_tokens::airdrop(&id, airdrop.unwrap()).await {
-        error!("{} InternalServerError: {}", id, err);
-        return HttpResponse::with_body(StatusCode::INTERNAL_SERVER_ERROR, err.to_string());
+    let mut airdrop = airdrop.unwrap();
+    airdrop.client_ip = client_ip(&req);
+    airdrop.max_amount = caller.max_amount();
+    let admission = admit(
+        &id,
+        &req,
+        &caller,
+        "request_sol",
+        limits::Token::Sol,
+        &airdrop.wallet,
+        airdrop.whole_amount(),
+    )
+    .await;
+    if let Err(err) = admission {
+        return error_response(&id, err);
     }
+    let reservation = admission.unwrap();
 
-    HttpResponse::with_body(StatusCode::OK, String::default())
+    if config::rpc_async_airdrop() {
+        return spawn_job(id, move |id| {
+            let airdrop = async move { sol_token::airdrop(&id, airdrop).await };
+            release_on_failure(airdrop, reservation)
+        });
+    }
+
+    match release_on_failure(sol_token::airdrop(&id, airdrop), reservation).await {
+        Ok(receipt) => json_response(StatusCode::OK, &receipt),
+        Err(err) => {
+            error!("{} Failed: {:#}", id, err);
+            error_response(&id, Error::from_report(err))
+        }
+    }
+}
+
+/// Handles a request for list of available SPL mints.
+async fn handle_request_spl_list() -> impl Responder {
+    let id = id::generate();
+    let counter = active_requests::increment();
+
+    println!();
+    info!("{} Handling request for list of SPL...", id);
+    info!("{} Active requests: {}", id, counter);
+
+    json_response(StatusCode::OK, &config::solana_spl_mints())
+}
+
+/// Handles a request for SPL tokens airdrop.
+async fn handle_request_spl(req: HttpRequest, body: Bytes) -> impl Responder {
+    let id = id::generate();
+    let counter = active_requests::increment();
+
+    println!();
+    info!("{} Handling request for SPL Airdrop...", id);
+    info!("{} Active requests: {}", id, counter);
+
+    let caller = auth::authorize(&id, "request_spl", authorization(&req));
+    if let Err(err) = caller {
+        error!("{} Unauthorized: {}", id, err);
+        return error_response(&id, err.into());
+    }
+    let caller = caller.unwrap();
+
+    let input = String::from_utf8(body.to_vec());
+    if let Err(err) = input {
+        error!("{} BadRequest (body): {}", id, err);
+        return error_response(&id, Error::BadBody(err.to_string()));
+    }
+
+    let input = input.unwrap();
+    let airdrop = serde_json::from_str::<spl_tokens::Airdrop>(&input);
+    if let Err(err) = airdrop {
+        error!("{} BadRequest (json): {} in '{}'", id, err, input);
+        return error_response(&id, Error::BadJson(err.to_string()));
+    }
+
+    let mut airdrop = airdrop.unwrap();
+    airdrop.client_ip = client_ip(&req);
+    airdrop.max_amount = caller.max_amount();
+    let admission = admit(
+        &id,
+        &req,
+        &caller,
+        "request_spl",
+        limits::Token::Spl,
+        &airdrop.wallet,
+        airdrop.amount,
+    )
+    .await;
+    if let Err(err) = admission {
+        return error_response(&id, err);
+    }
+    let reservation = admission.unwrap();
+
+    if config::rpc_async_airdrop() {
+        return spawn_job(id, move |id| {
+            let airdrop = async move { spl_tokens::airdrop(&id, airdrop).await };
+            release_on_failure(airdrop, reservation)
+        });
+    }
+
+    match release_on_failure(spl_tokens::airdrop(&id, airdrop), reservation).await {
+        Ok(receipt) => json_response(StatusCode::OK, &receipt),
+        Err(err) => {
+            error!("{} Failed: {:#}", id, err);
+            error_response(&id, Error::from_report(err))
+        }
+    }
+}
+
+/// Handles a request for status of an airdrop job.
+async fn handle_request_status(job_id: Path<String>) -> impl Responder {
+    let id = id::generate();
+    let counter = active_requests::increment();
+
+    println!();
+    info!("{} Handling request for status of job {}...", id, job_id);
+    info!("{} Active requests: {}", id, counter);
+
+    match jobs::get(&job_id) {
+        Some(job) => json_response(StatusCode::OK, &job),
+        None => {
+            error!("{} NotFound (job): {}", id, job_id);
+            error_response(&id, Error::UnknownJob(job_id.into_inner()))
+        }
+    }
+}
+
+/// Handles a liveness probe.
+async fn handle_health() -> impl Responder {
+    json_response(StatusCode::OK, &serde_json::json!({ "status": "ok" }))
+}
+
+/// Handles a readiness probe: checks upstreams and balances.
+async fn handle_ready() -> impl Responder {
+    let id = id::generate();
+    let report = health::ready(&id).await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    json_response(status, &report)
+}
+
+/// Handles a request for metrics in Prometheus format.
+async fn handle_metrics() -> impl Responder {
+    let id = id::generate();
+
+    if config::solana_enabled() {
+        match neon_token::balances().await {
+            Ok(balances) => {
+                for b in balances {
+                    metrics::set_balance(&b.operator, "SOL", b.sol);
+                    metrics::set_balance(&b.operator, "NEON", b.neon);
+                }
+            }
+            Err(err) => {
+                error!("{} Failed (balance): {:#}", id, err);
+                metrics::rpc_error("solana");
+            }
+        }
+    }
+
+    if config::web3_enabled() {
+        match erc20_tokens::balances(&id).await {
+            Ok(balances) => {
+                for operator in balances {
+                    for b in operator.tokens {
+                        if let Some(amount) = b.amount {
+                            metrics::set_balance(&operator.operator, &b.token, amount);
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                error!("{} Failed (balances): {:#}", id, err);
+                metrics::rpc_error("web3");
+            }
+        }
+    }
+
+    let mut response =
+        HttpResponse::with_body(StatusCode::OK, metrics::render(active_requests::get()));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    response
+}
+
+/// Enqueues the airdrop job and responds with its id immediately.
+fn spawn_job<A, F, T>(id: id::ReqId, airdrop: A) -> HttpResponse<String>
+where
+    A: FnOnce(id::ReqId) -> F,
+    F: Future<Output = Result<T>> + 'static,
+    T: jobs::Outcome,
+{
+    let id = jobs::enqueue(&id);
+    let job_id = id.job().unwrap_or_default().to_owned();
+    let airdrop = airdrop(id.clone());
+    actix_web::rt::spawn(async move {
+        match airdrop.await {
+            Ok(receipt) => jobs::finished(&id, &receipt),
+            Err(err) => {
+                error!("{} Job failed: {:#}", id, err);
+                let err = Error::from_report(err);
+                if let Error::UpstreamRpc(rpc) = err {
+                    metrics::rpc_error(rpc);
+                }
+                jobs::failed(&id, err.body(&id));
+            }
+        }
+    });
+    json_response(
+        StatusCode::ACCEPTED,
+        &serde_json::json!({ "job_id": job_id }),
+    )
+}
+
+/// Returns IP address of the client (respects X-Forwarded-For headers of trusted proxies).
+fn client_ip(req: &HttpRequest) -> Option<String> {
+    let peer = req.peer_addr()?.ip();
+    let forwarded_for = req
+        .headers()
+        .get_all(X_FORWARDED_FOR)
+        .filter_map(|v| v.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(",");
+    let forwarded_for = (!forwarded_for.is_empty()).then_some(forwarded_for.as_str());
+    Some(limits::client_ip(peer, forwarded_for).to_string())
+}
+
+/// Verifies captcha of an anonymous caller and checks quotas of the caller.
+/// `amount` is expected in whole tokens.
+/// Returns the taken quota, which is released if the airdrop fails.
+async fn admit(
+    id: &id::ReqId,
+    req: &HttpRequest,
+    caller: &auth::Caller,
+    endpoint: &str,
+    token: limits::Token,
+    wallet: &str,
+    amount: u64,
+) -> Result<Option<limits::Reservation>, Error> {
+    let reservation = match caller {
+        auth::Caller::Anonymous => {
+            let ip = client_ip(req);
+            captcha::verify(id, endpoint, captcha_token(req), ip.as_deref())
+                .await
+                .map_err(|err| {
+                    error!("{} Forbidden (captcha): {}", id, err);
+                    err
+                })?;
+            limits::check(id, token, wallet, ip.as_deref(), amount).map_err(|err| {
+                error!("{} TooManyRequests: {}", id, err);
+                err
+            })?
+        }
+        auth::Caller::Key(key) => {
+            Some(limits::check_key(id, token, key, amount).map_err(|err| {
+                error!("{} TooManyRequests: {}", id, err);
+                err
+            })?)
+        }
+    };
+    Ok(reservation)
+}
+
+/// Runs the airdrop and releases the quota taken by the request if the airdrop fails.
+async fn release_on_failure<F, T>(airdrop: F, reservation: Option<limits::Reservation>) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let result = airdrop.await;
+    if let (Err(_), Some(reservation)) = (&result, reservation) {
+        reservation.release();
+    }
+    result
+}
+
+/// Returns value of the Authorization header, if any.
+fn authorization(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+}
+
+/// Returns captcha token sent by the client, if any.
+fn captcha_token(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(CAPTCHA_TOKEN)
+        .and_then(|v| v.to_str().ok())
+}
+
+/// Builds response with the value serialized to JSON.
+fn json_response<T: serde::Serialize>(status: StatusCode, value: &T) -> HttpResponse<String> {
+    let body = serde_json::to_string(value).unwrap_or_default();
+    let mut response = HttpResponse::with_body(status, body);
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// Builds JSON response `{"code": ..., "message": ..., "req_id": ...}` for the error.
+fn error_response(id: &id::ReqId, err: Error) -> HttpResponse<String> {
+    if let Error::UpstreamRpc(rpc) = err {
+        metrics::rpc_error(rpc);
+    }
+    let mut response = json_response(err.status(), &err.body(id));
+    if let Error::RateLimited(err) = err {
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            header::HeaderValue::from(err.retry_after_secs()),
+        );
+    }
+    response
 }
 
 /// Handles a request for graceful shutdown.
@@ -222,14 +726,14 @@ async fn handle_request_stop(body: Bytes) -> impl Responder {
     let input = String::from_utf8(body.to_vec());
     if let Err(err) = input {
         error!("{} BadRequest (body): {}", id, err);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadBody(err.to_string()));
     }
 
     let input = input.unwrap();
     let stop = serde_json::from_str::<Stop>(&input);
     if let Err(err) = stop {
         error!("{} BadRequest (json): {} in '{}'", id, err, input);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        return error_response(&id, Error::BadJson(err.to_string()));
     }
 
     let delay = stop.unwrap().delay;
@@ -240,8 +744,8 @@ async fn handle_request_stop(body: Bytes) -> impl Responder {
 
     let terminate = signal::kill(Pid::this(), signal::SIGTERM);
     if let Err(err) = terminate {
-        error!("{} BadRequest (terminate): {}", id, err);
-        return HttpResponse::with_body(StatusCode::BAD_REQUEST, err.to_string());
+        error!("{} Failed (terminate): {}", id, err);
+        return error_response(&id, Error::Internal);
     }
 
     HttpResponse::with_body(StatusCode::OK, String::default())
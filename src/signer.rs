@@ -0,0 +1,322 @@
+//! Faucet signers module.
+
+use std::path::PathBuf;
+use std::str::FromStr as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use eyre::Result;
+use secp256k1::SecretKey;
+use tracing::debug;
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::keypair::{keypair_from_seed, Keypair};
+use solana_sdk::signer::SignerError;
+
+use web3::api::{Accounts, Eth, Namespace as _};
+use web3::signing::SecretKeyRef;
+use web3::transports::Http;
+use web3::types::{Address, Bytes, TransactionParameters, TransactionRequest};
+use web3::Transport;
+
+use crate::config::{self, SIGNER_ENV, SIGNER_KEYSTORE, SIGNER_REMOTE};
+use crate::{ethereum, pool};
+
+/// Timeout of a request to the remote signer.
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Represents the signer errors.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to decrypt keystore {1:?}: {0}")]
+    Keystore(#[source] eth_keystore::KeystoreError, PathBuf),
+
+    #[error("Environment variable '{0}' is not set")]
+    MissingEnv(String),
+
+    #[error("Invalid private key from {0}")]
+    InvalidKey(String),
+
+    #[error("Invalid public key '{0}' of the remote signer")]
+    InvalidPublic(String),
+}
+
+/// Represents the Solana operator.
+pub enum SolanaSigner {
+    Local(Keypair),
+    Remote(RemoteSigner),
+}
+
+/// Represents a key kept by a remote signer.
+pub struct RemoteSigner {
+    url: String,
+    pubkey: Pubkey,
+    /// HTTP client (created on first use: it cannot be created in async context).
+    client: Mutex<Option<reqwest::blocking::Client>>,
+}
+
+impl solana_sdk::signer::Signer for SolanaSigner {
+    fn try_pubkey(&self) -> std::result::Result<Pubkey, SignerError> {
+        match self {
+            SolanaSigner::Local(keypair) => keypair.try_pubkey(),
+            SolanaSigner::Remote(remote) => Ok(remote.pubkey),
+        }
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        match self {
+            SolanaSigner::Local(keypair) => keypair.try_sign_message(message),
+            SolanaSigner::Remote(remote) => remote.sign(message),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SignRequest {
+    pubkey: String,
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+impl RemoteSigner {
+    /// Requests signature of the message: `POST <url>/sign`. Blocks the thread.
+    fn sign(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        let url = format!("{}/sign", self.url.trim_end_matches('/'));
+        debug!("Requesting signature from {}...", url);
+        let response: SignResponse = self
+            .client()?
+            .post(&url)
+            .json(&SignRequest {
+                pubkey: self.pubkey.to_string(),
+                message: base64::encode(message),
+            })
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| SignerError::Connection(e.to_string()))?;
+
+        let signature = Signature::from_str(&response.signature)
+            .map_err(|e| SignerError::Protocol(e.to_string()))?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Protocol(format!(
+                "Invalid signature from {}",
+                url
+            )));
+        }
+        Ok(signature)
+    }
+
+    fn client(&self) -> std::result::Result<reqwest::blocking::Client, SignerError> {
+        let mut client = self.client.lock().unwrap();
+        if let Some(client) = client.as_ref() {
+            return Ok(client.clone());
+        }
+        let created = reqwest::blocking::Client::builder()
+            .timeout(REMOTE_TIMEOUT)
+            .build()
+            .map_err(|e| SignerError::Connection(e.to_string()))?;
+        Ok(client.insert(created).clone())
+    }
+}
+
+/// Returns the Solana operator defined by `solana.signer`.
+fn solana_operator() -> Result<SolanaSigner> {
+    let signer = config::solana_signer();
+    let keypair = match signer.backend.as_str() {
+        SIGNER_KEYSTORE => solana_keypair(&decrypt(&signer)?, "keystore")?,
+        SIGNER_ENV => {
+            let key = read_env(&signer.key_env)?;
+            let bytes = if key.trim_start().starts_with('[') {
+                serde_json::from_str::<Vec<u8>>(&key).ok()
+            } else {
+                solana_sdk::bs58::decode(key.trim()).into_vec().ok()
+            };
+            let bytes = bytes.ok_or_else(|| Error::InvalidKey(signer.key_env.clone()))?;
+            solana_keypair(&bytes, &signer.key_env)?
+        }
+        SIGNER_REMOTE => {
+            let pubkey = Pubkey::from_str(&signer.public)
+                .map_err(|_| Error::InvalidPublic(signer.public.clone()))?;
+            return Ok(SolanaSigner::Remote(RemoteSigner {
+                url: signer.url,
+                pubkey,
+                client: Mutex::new(None),
+            }));
+        }
+        _ => config::solana_operator_keypair()?,
+    };
+    Ok(SolanaSigner::Local(keypair))
+}
+
+/// Returns all Solana operators: `solana.signer` and `solana.operator_keyfiles`.
+pub fn solana_operators() -> Result<Vec<SolanaSigner>> {
+    let mut operators = vec![solana_operator()?];
+    for keypair in config::solana_operator_keypairs()? {
+        operators.push(SolanaSigner::Local(keypair));
+    }
+    Ok(operators)
+}
+
+/// Makes keypair from 64 bytes of a keypair or 32 bytes of a secret key.
+fn solana_keypair(bytes: &[u8], source: &str) -> Result<Keypair> {
+    let keypair = if bytes.len() == 32 {
+        keypair_from_seed(bytes).ok()
+    } else {
+        Keypair::from_bytes(bytes).ok()
+    };
+    Ok(keypair.ok_or_else(|| Error::InvalidKey(source.into()))?)
+}
+
+/// Represents the Ethereum operator (admin).
+pub enum EthereumSigner {
+    Local(SecretKey),
+    Remote { signer: Http, address: Address },
+}
+
+impl EthereumSigner {
+    /// Returns address of the operator.
+    pub fn address(&self) -> Address {
+        use web3::signing::Key as _;
+        match self {
+            EthereumSigner::Local(key) => web3::signing::SecretKeyRef::new(key).address(),
+            EthereumSigner::Remote { address, .. } => *address,
+        }
+    }
+}
+
+/// Returns the Ethereum operator defined by `web3.signer`.
+fn web3_operator() -> Result<EthereumSigner> {
+    let signer = config::web3_signer();
+    let key = match signer.backend.as_str() {
+        SIGNER_KEYSTORE => SecretKey::from_slice(&decrypt(&signer)?)
+            .map_err(|_| Error::InvalidKey("keystore".into()))?,
+        SIGNER_ENV => {
+            let key = read_env(&signer.key_env)?;
+            ethereum::strip_0x_prefix(key.trim())
+                .parse()
+                .map_err(|_| Error::InvalidKey(signer.key_env.clone()))?
+        }
+        SIGNER_REMOTE => {
+            let address = ethereum::address_from_str(&signer.public)
+                .map_err(|_| Error::InvalidPublic(signer.public.clone()))?;
+            return Ok(EthereumSigner::Remote {
+                signer: Http::new(&signer.url)?,
+                address,
+            });
+        }
+        _ => config::web3_private_key().parse()?,
+    };
+    Ok(EthereumSigner::Local(key))
+}
+
+/// Returns all Ethereum operators: `web3.signer` and `web3.private_keys`.
+pub fn web3_operators() -> Result<Vec<EthereumSigner>> {
+    let mut operators = vec![web3_operator()?];
+    for key in config::web3_private_keys() {
+        operators.push(EthereumSigner::Local(key.parse()?));
+    }
+    Ok(operators)
+}
+
+/// Signs a transaction (with nonce and fees set) by the operator.
+/// The remote signer must support `eth_signTransaction`.
+pub async fn sign_transaction<T: Transport>(
+    eth: &Eth<T>,
+    signer: &EthereumSigner,
+    tx: TransactionRequest,
+) -> web3::Result<Bytes> {
+    #[derive(serde::Deserialize)]
+    struct Signed {
+        raw: Bytes,
+    }
+
+    match signer {
+        EthereumSigner::Local(key) => {
+            let tx = TransactionParameters {
+                chain_id: Some(pool::web3_chain_id(eth).await?),
+                nonce: tx.nonce,
+                to: tx.to,
+                gas: tx.gas.unwrap_or_default(),
+                gas_price: tx.gas_price,
+                data: tx.data.unwrap_or_default(),
+                transaction_type: tx.transaction_type,
+                max_fee_per_gas: tx.max_fee_per_gas,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                ..Default::default()
+            };
+            let signed = Accounts::new(eth.transport().clone())
+                .sign_transaction(tx, SecretKeyRef::new(key))
+                .await?;
+            Ok(signed.raw_transaction)
+        }
+        EthereumSigner::Remote { signer, .. } => {
+            let signed = signer
+                .execute("eth_signTransaction", vec![web3::helpers::serialize(&tx)])
+                .await?;
+            if signed.is_string() {
+                serde_json::from_value::<Bytes>(signed)
+            } else {
+                serde_json::from_value::<Signed>(signed).map(|s| s.raw)
+            }
+            .map_err(|e| web3::Error::Decoder(e.to_string()))
+        }
+    }
+}
+
+/// Decrypts Ethereum V3 keystore with passphrase from the environment.
+fn decrypt(signer: &config::Signer) -> Result<Vec<u8>> {
+    let passphrase = read_env(&signer.passphrase_env)?;
+    Ok(eth_keystore::decrypt_key(&signer.keystore, passphrase)
+        .map_err(|e| Error::Keystore(e, signer.keystore.clone()))?)
+}
+
+fn read_env(name: &str) -> Result<String, Error> {
+    std::env::var(name).map_err(|_| Error::MissingEnv(name.into()))
+}
+
+#[test]
+fn test_keystore() {
+    let passphrase_env = "FAUCET_TEST_KEYSTORE_PASSPHRASE";
+    std::env::set_var(passphrase_env, "faucet");
+
+    let bytes = decrypt(&config::Signer {
+        keystore: "tests/fixtures/solana_keystore.json".into(),
+        passphrase_env: passphrase_env.into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let keypair = solana_keypair(&bytes, "keystore").unwrap();
+    assert_eq!(
+        keypair.to_bytes(),
+        keypair_from_seed(&[7; 32]).unwrap().to_bytes()
+    );
+
+    let bytes = decrypt(&config::Signer {
+        keystore: "tests/fixtures/web3_keystore.json".into(),
+        passphrase_env: passphrase_env.into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let key = EthereumSigner::Local(SecretKey::from_slice(&bytes).unwrap());
+    let expected: SecretKey = "0000000000000000000000000000000000000000000000000000000000000ace"
+        .parse()
+        .unwrap();
+    assert_eq!(key.address(), EthereumSigner::Local(expected).address());
+
+    std::env::set_var(passphrase_env, "wrong");
+    assert!(decrypt(&config::Signer {
+        keystore: "tests/fixtures/web3_keystore.json".into(),
+        passphrase_env: passphrase_env.into(),
+        ..Default::default()
+    })
+    .is_err());
+}
@@ -0,0 +1,113 @@
+//! Faucet SOL token module.
+
+use eyre::{eyre, Result};
+use tracing::info;
+
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::Error;
+use crate::operators::{self, SolanaAsset};
+use crate::{config, id::ReqId, ledger, metrics, solana};
+
+/// Represents packet of information needed for single airdrop operation.
+#[derive(Debug, serde::Deserialize)]
+pub struct Airdrop {
+    /// Solana address of the recipient.
+    pub wallet: String,
+    /// Amount of SOL to be received.
+    pub amount: u64,
+    /// Specifies amount in whole SOL (false, default) or in lamports (true).
+    #[serde(default)]
+    pub in_lamports: bool,
+    /// IP address of the client (for the ledger).
+    #[serde(skip)]
+    pub client_ip: Option<String>,
+    /// Largest amount per request overriding the default (for API keys).
+    #[serde(skip)]
+    pub max_amount: Option<u64>,
+}
+
+impl Airdrop {
+    /// Returns requested amount in whole SOL (rounded up).
+    pub fn whole_amount(&self) -> u64 {
+        if !self.in_lamports {
+            return self.amount;
+        }
+        let whole = self.amount / LAMPORTS_PER_SOL;
+        if whole * LAMPORTS_PER_SOL < self.amount {
+            whole + 1
+        } else {
+            whole
+        }
+    }
+}
+
+/// Processes the airdrop: transfers lamports from the operator.
+pub async fn airdrop(id: &ReqId, params: Airdrop) -> Result<solana::Receipt> {
+    use std::str::FromStr as _;
+
+    info!("{} Processing SOL {:?}...", id, params);
+
+    let max_amount = params
+        .max_amount
+        .unwrap_or_else(config::solana_sol_max_amount);
+    let limit = if !params.in_lamports {
+        max_amount
+    } else {
+        max_amount
+            .checked_mul(LAMPORTS_PER_SOL)
+            .ok_or_else(|| eyre!("Overflow {}*{}", max_amount, LAMPORTS_PER_SOL))?
+    };
+
+    if params.amount > limit {
+        return Err(Error::AmountOverLimit(params.amount, limit).into());
+    }
+
+    let recipient = Pubkey::from_str(&params.wallet)
+        .map_err(|_| Error::InvalidAddress(params.wallet.clone()))?;
+
+    let lamports = if params.in_lamports {
+        params.amount
+    } else {
+        params
+            .amount
+            .checked_mul(LAMPORTS_PER_SOL)
+            .ok_or_else(|| eyre!("Overflow {}*{}", params.amount, LAMPORTS_PER_SOL))?
+    };
+    let operator = operators::solana(id, SolanaAsset::Sol, lamports).await?;
+
+    let key = ledger::open(
+        id,
+        ledger::Entry {
+            token: "SOL".into(),
+            wallet: params.wallet.clone(),
+            amount: params.amount,
+            in_fractions: params.in_lamports,
+            client_ip: params.client_ip.clone(),
+        },
+    )
+    .await;
+
+    match solana::transfer_lamports(id, operator, recipient, lamports).await {
+        Ok(receipt) => {
+            ledger::close(
+                id,
+                key,
+                ledger::Status::Success,
+                Some(receipt.signature.clone()),
+                None,
+            )
+            .await;
+            metrics::airdropped("SOL", lamports as f64 / LAMPORTS_PER_SOL as f64);
+            Ok(receipt)
+        }
+        Err(e) => {
+            ledger::close(id, key, ledger::Status::Failed, None, Some(e.to_string())).await;
+            Err(e.wrap_err(format!(
+                "solana::transfer_lamports(operator, {})",
+                recipient
+            )))
+        }
+    }
+}
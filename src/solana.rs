@@ -1,6 +1,7 @@
 //! Faucet Solana utilities module.
 
 use std::str::FromStr as _;
+use std::sync::Arc;
 
 use eyre::{eyre, Result, WrapErr as _};
 use tracing::debug;
@@ -9,17 +10,41 @@ use solana_client::rpc_client::RpcClient;
 use solana_sdk::instruction::{AccountMeta, Instruction};
 use solana_sdk::message::Message;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Signer as _;
-use solana_sdk::signer::keypair::Keypair;
+use solana_sdk::signature::{Signature, Signer as _};
+use solana_sdk::system_instruction;
 use solana_sdk::system_program;
 use solana_sdk::transaction::Transaction;
 
 use crate::config;
-use crate::{ethereum, id::ReqId};
+use crate::signer::SolanaSigner;
+use crate::{ethereum, id::ReqId, jobs, pool};
+
+/// Default compute units of a transaction (used when only a fee is requested).
+const DEFAULT_COMPUTE_UNITS: u32 = 200_000;
+
+/// Represents a confirmed Solana transaction.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Receipt {
+    /// Signature of the transaction.
+    pub signature: String,
+    /// Slot of the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+}
+
+impl jobs::Outcome for Receipt {
+    fn status(&self) -> jobs::Status {
+        jobs::Status::Confirmed
+    }
+}
 
 /// Converts amount of tokens from whole value to fractions (usually 10E-9).
 pub fn convert_whole_to_fractions(amount: u64) -> Result<u64> {
-    let decimals = config::solana_token_mint_decimals();
+    convert_whole_to_fractions_with(amount, config::solana_token_mint_decimals())
+}
+
+/// Converts amount of tokens from whole value to fractions of a mint with `decimals`.
+pub fn convert_whole_to_fractions_with(amount: u64, decimals: u8) -> Result<u64> {
     let factor = 10_u64
         .checked_pow(decimals as u32)
         .ok_or_else(|| eyre!("Overflow 10^{}", decimals))?;
@@ -33,11 +58,11 @@ pub fn convert_whole_to_fractions(amount: u64) -> Result<u64> {
 /// When `in_fractions` == true, amount is treated as amount in galans (10E-9).
 pub async fn deposit_token(
     id: &ReqId,
-    signer: Keypair,
+    signer: Arc<SolanaSigner>,
     ether_address: ethereum::Address,
     amount: u64,
     in_fractions: bool,
-) -> Result<()> {
+) -> Result<Receipt> {
     let evm_loader_id = Pubkey::from_str(&config::solana_evm_loader()).wrap_err_with(|| {
         eyre!(
             "config::solana_evm_loader returns {}",
@@ -64,9 +89,8 @@ pub async fn deposit_token(
     let ether_pubkey = ether_address_to_solana_pubkey(&ether_address, &evm_loader_id).0;
 
     let id = id.to_owned();
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        let client =
-            RpcClient::new_with_commitment(config::solana_url(), config::solana_commitment());
+    tokio::task::spawn_blocking(move || -> Result<Receipt> {
+        let client = pool::solana_client();
 
         let amount = if in_fractions {
             amount
@@ -74,7 +98,8 @@ pub async fn deposit_token(
             convert_whole_to_fractions(amount)?
         };
 
-        let instructions = vec![
+        let mut instructions = compute_budget_instructions(&id);
+        instructions.extend([
             spl_memo(&id, &signer_pubkey),
             spl_approve_instruction(
                 &id,
@@ -94,29 +119,146 @@ pub async fn deposit_token(
                 spl_token::id(),
                 signer_pubkey,
             ),
+        ]);
+
+        send_and_confirm(&id, &client, &instructions, &signer)
+    })
+    .await?
+}
+
+/// Transfers `lamports` from the signer's account to the recipient.
+pub async fn transfer_lamports(
+    id: &ReqId,
+    signer: Arc<SolanaSigner>,
+    recipient: Pubkey,
+    lamports: u64,
+) -> Result<Receipt> {
+    let id = id.to_owned();
+    tokio::task::spawn_blocking(move || -> Result<Receipt> {
+        let client = pool::solana_client();
+
+        let signer_pubkey = signer.pubkey();
+        debug!("{} Instruction: SystemInstruction::Transfer", id);
+        debug!("{} recipient = {}", id, recipient);
+        debug!("{} lamports = {}", id, lamports);
+        let instructions = vec![
+            spl_memo(&id, &signer_pubkey),
+            system_instruction::transfer(&signer_pubkey, &recipient, lamports),
         ];
 
-        debug!(
-            "{} Creating message with {} instructions...",
-            id,
-            instructions.len()
-        );
-        let message = Message::new(&instructions, Some(&signer_pubkey));
-        debug!("{} Creating transaction...", id);
-        let mut tx = Transaction::new_unsigned(message);
-        debug!("{} Getting latest blockhash...", id);
-        let blockhash = client.get_latest_blockhash()?;
-        debug!("{} Signing transaction...", id);
-        tx.try_sign(&[&signer], blockhash)?;
-        debug!("{} Sending and confirming transaction...", id);
-        client.send_and_confirm_transaction(&tx)?;
-        debug!("{} Transaction is confirmed", id);
-
-        Ok(())
+        send_and_confirm(&id, &client, &instructions, &signer)
     })
     .await?
 }
 
+/// Transfers `amount` (in fractions) of SPL tokens from the signer's associated
+/// token account to the recipient's one; creates the latter if missing.
+pub async fn transfer_spl(
+    id: &ReqId,
+    signer: Arc<SolanaSigner>,
+    mint: Pubkey,
+    decimals: u8,
+    recipient: Pubkey,
+    amount: u64,
+) -> Result<Receipt> {
+    use spl_associated_token_account::{
+        create_associated_token_account, get_associated_token_address,
+    };
+
+    let id = id.to_owned();
+    tokio::task::spawn_blocking(move || -> Result<Receipt> {
+        let client = pool::solana_client();
+
+        let signer_pubkey = signer.pubkey();
+        let source = get_associated_token_address(&signer_pubkey, &mint);
+        let destination = get_associated_token_address(&recipient, &mint);
+
+        let mut instructions = vec![spl_memo(&id, &signer_pubkey)];
+        let exists = client
+            .get_account_with_commitment(&destination, config::solana_commitment())?
+            .value
+            .is_some();
+        if !exists {
+            debug!("{} Instruction: Create associated token account", id);
+            debug!("{} destination = {}", id, destination);
+            instructions.push(create_associated_token_account(
+                &signer_pubkey,
+                &recipient,
+                &mint,
+            ));
+        }
+
+        debug!("{} Instruction: TokenInstruction::TransferChecked", id);
+        debug!("{} source = {}", id, source);
+        debug!("{} destination = {}", id, destination);
+        debug!("{} amount = {}", id, amount);
+        instructions.push(spl_token::instruction::transfer_checked(
+            &spl_token::id(),
+            &source,
+            &mint,
+            &destination,
+            &signer_pubkey,
+            &[],
+            amount,
+            decimals,
+        )?);
+
+        send_and_confirm(&id, &client, &instructions, &signer)
+    })
+    .await?
+}
+
+/// Returns number of decimals of the SPL mint.
+pub async fn get_mint_decimals(mint: Pubkey) -> Result<u8> {
+    tokio::task::spawn_blocking(move || -> Result<u8> {
+        let client = pool::solana_client();
+        Ok(client.get_token_supply(&mint)?.decimals)
+    })
+    .await?
+}
+
+/// Signs the instructions into a transaction, sends it and waits for confirmation.
+fn send_and_confirm(
+    id: &ReqId,
+    client: &RpcClient,
+    instructions: &[Instruction],
+    signer: &SolanaSigner,
+) -> Result<Receipt> {
+    debug!(
+        "{} Creating message with {} instructions...",
+        id,
+        instructions.len()
+    );
+    let message = Message::new(instructions, Some(&signer.pubkey()));
+    debug!("{} Creating transaction...", id);
+    let mut tx = Transaction::new_unsigned(message);
+    debug!("{} Getting latest blockhash...", id);
+    let blockhash = client.get_latest_blockhash()?;
+    debug!("{} Signing transaction...", id);
+    tx.try_sign(&[signer], blockhash)?;
+    jobs::sent(id, tx.signatures[0].to_string());
+    debug!("{} Sending and confirming transaction...", id);
+    let signature = client.send_and_confirm_transaction(&tx)?;
+    debug!("{} Transaction {} is confirmed", id, signature);
+
+    Ok(Receipt {
+        signature: signature.to_string(),
+        slot: get_slot(client, &signature),
+    })
+}
+
+/// Returns slot of a confirmed transaction.
+fn get_slot(client: &RpcClient, signature: &Signature) -> Option<u64> {
+    client
+        .get_signature_statuses(&[*signature])
+        .ok()?
+        .value
+        .into_iter()
+        .next()
+        .flatten()
+        .map(|status| status.slot)
+}
+
 /// Maps an Ethereum address into a Solana address.
 fn ether_address_to_solana_pubkey(
     ether_address: &ethereum::Address,
@@ -131,6 +273,38 @@ fn ether_address_to_solana_pubkey(
     )
 }
 
+/// Returns compute budget instructions requested by the EVM Loader (with priority fee).
+fn compute_budget_instructions(id: &ReqId) -> Vec<Instruction> {
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    let mut instructions = vec![];
+
+    let mut units = config::solana_compute_budget_units();
+    let fee = config::solana_compute_budget_additional_fee()
+        .saturating_add(config::solana_priority_fee());
+    if units > 0 || fee > 0 {
+        if units == 0 {
+            units = DEFAULT_COMPUTE_UNITS;
+        }
+        debug!(
+            "{} Instruction: ComputeBudgetInstruction::RequestUnits({}, {})",
+            id, units, fee
+        );
+        instructions.push(ComputeBudgetInstruction::request_units(units, fee));
+    }
+
+    let heap_frame = config::solana_compute_budget_heap_frame();
+    if heap_frame > 0 {
+        debug!(
+            "{} Instruction: ComputeBudgetInstruction::RequestHeapFrame({})",
+            id, heap_frame
+        );
+        instructions.push(ComputeBudgetInstruction::request_heap_frame(heap_frame));
+    }
+
+    instructions
+}
+
 fn spl_memo(id: &ReqId, pubkey: &Pubkey) -> Instruction {
     debug!("{} Instruction: SPL Memo", id);
     let memo = format!("Neon Faucet {}", id.as_str());
@@ -0,0 +1,189 @@
+//! Faucet SPL tokens module.
+
+use std::collections::HashMap;
+use std::str::FromStr as _;
+
+use eyre::Result;
+use futures_locks::RwLock;
+use tracing::{debug, error, info};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::Error;
+use crate::operators::{self, SolanaAsset};
+use crate::{config, id::ReqId, jobs, ledger, metrics, solana};
+
+lazy_static::lazy_static! {
+    /// Decimals of SPL mints.
+    static ref MINTS: RwLock<HashMap<String, u8>> = RwLock::new(HashMap::new());
+}
+
+/// Represents packet of information needed for an airdrop operation.
+#[derive(Debug, serde::Deserialize)]
+pub struct Airdrop {
+    /// Solana address of the recipient.
+    pub wallet: String,
+    /// SPL mint address. If missing, do all mints.
+    mint: Option<String>,
+    /// Amount of a token to be received (in whole tokens).
+    pub amount: u64,
+    /// IP address of the client (for the ledger).
+    #[serde(skip)]
+    pub client_ip: Option<String>,
+    /// Largest amount per request overriding the default (for API keys).
+    #[serde(skip)]
+    pub max_amount: Option<u64>,
+}
+
+/// Represents result of a single mint transfer.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Transfer {
+    /// SPL mint address.
+    pub mint: String,
+    /// Transaction of a successful transfer.
+    #[serde(flatten)]
+    pub receipt: Option<solana::Receipt>,
+    /// Code of the error of a failed transfer (see `error::Error::code`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
+    /// Public message of the error of a failed transfer (details are in the log).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Transfer {
+    fn sent(mint: &str, receipt: solana::Receipt) -> Self {
+        Transfer {
+            mint: mint.to_owned(),
+            receipt: Some(receipt),
+            code: None,
+            error: None,
+        }
+    }
+
+    fn failed(mint: &str, error: &eyre::Report) -> Self {
+        let (code, message) = Error::public(error);
+        Transfer {
+            mint: mint.to_owned(),
+            receipt: None,
+            code: Some(code),
+            error: Some(message),
+        }
+    }
+}
+
+/// Represents result of the airdrop.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct Receipt {
+    pub transactions: Vec<Transfer>,
+}
+
+impl jobs::Outcome for Receipt {
+    fn status(&self) -> jobs::Status {
+        jobs::status_of(self.transactions.iter().map(|t| match t.receipt {
+            Some(_) => jobs::Status::Confirmed,
+            None => jobs::Status::Failed,
+        }))
+    }
+}
+
+/// Processes the airdrop: transfers tokens of each requested mint.
+pub async fn airdrop(id: &ReqId, params: Airdrop) -> Result<Receipt> {
+    info!("{} Processing SPL {:?}...", id, params);
+
+    let mut mints = config::solana_spl_mints();
+
+    if let Some(ref mint) = params.mint {
+        if !mints.contains(mint) {
+            return Err(Error::UnknownToken(mint.clone()).into());
+        }
+        mints = vec![mint.clone()];
+    }
+
+    let max_amount = params
+        .max_amount
+        .unwrap_or_else(config::solana_spl_max_amount);
+    if params.amount > max_amount {
+        return Err(Error::AmountOverLimit(params.amount, max_amount).into());
+    }
+
+    let recipient = Pubkey::from_str(&params.wallet)
+        .map_err(|_| Error::InvalidAddress(params.wallet.clone()))?;
+
+    let mut receipt = Receipt::default();
+    let mut first_error = None;
+    for mint in &mints {
+        match transfer(id, &params, mint, recipient).await {
+            Ok(r) => receipt.transactions.push(Transfer::sent(mint, r)),
+            Err(e) => {
+                receipt.transactions.push(Transfer::failed(mint, &e));
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    // The airdrop fails only if no mint is transferred
+    match first_error {
+        Some(e) if receipt.transactions.iter().all(|t| t.receipt.is_none()) => Err(e),
+        _ => Ok(receipt),
+    }
+}
+
+/// Transfers tokens of the mint from the chosen operator to the recipient.
+async fn transfer(
+    id: &ReqId,
+    params: &Airdrop,
+    mint: &str,
+    recipient: Pubkey,
+) -> Result<solana::Receipt> {
+    let mint_pubkey = Pubkey::from_str(mint)?;
+    let decimals = get_decimals(id, mint, mint_pubkey).await?;
+    let amount = solana::convert_whole_to_fractions_with(params.amount, decimals)?;
+    let operator = operators::solana(id, SolanaAsset::Token(mint_pubkey), amount).await?;
+
+    let key = ledger::open(
+        id,
+        ledger::Entry {
+            token: mint.to_owned(),
+            wallet: params.wallet.clone(),
+            amount: params.amount,
+            in_fractions: false,
+            client_ip: params.client_ip.clone(),
+        },
+    )
+    .await;
+
+    let r = solana::transfer_spl(id, operator, mint_pubkey, decimals, recipient, amount).await;
+
+    match r {
+        Ok(r) => {
+            ledger::close(
+                id,
+                key,
+                ledger::Status::Success,
+                Some(r.signature.clone()),
+                None,
+            )
+            .await;
+            metrics::airdropped(mint, params.amount as f64);
+            Ok(r)
+        }
+        Err(e) => {
+            error!("{} Failed transfer of mint {}: {}", id, mint, e);
+            ledger::close(id, key, ledger::Status::Failed, None, Some(e.to_string())).await;
+            Err(e.wrap_err(format!("solana::transfer_spl({}, {})", mint, recipient)))
+        }
+    }
+}
+
+/// Returns decimals of the mint; queries them once and keeps in the cache.
+async fn get_decimals(id: &ReqId, mint: &str, mint_pubkey: Pubkey) -> Result<u8> {
+    if let Some(decimals) = MINTS.read().await.get(mint) {
+        return Ok(*decimals);
+    }
+
+    let decimals = solana::get_mint_decimals(mint_pubkey).await?;
+    debug!("{} SPL mint {} has decimals {}", id, mint, decimals);
+    MINTS.write().await.insert(mint.to_owned(), decimals);
+    Ok(decimals)
+}
@@ -0,0 +1,6 @@
+const char NEON_SEED_VERSION[1] = "1";
+const char NEON_TOKEN_MINT[44] = "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g";
+const char NEON_TOKEN_MINT_DECIMALS[1] = "9";
+const char NEON_COMPUTE_UNITS[6] = "500000";
+const char NEON_HEAP_FRAME[6] = "262144";
+const char NEON_ADDITIONAL_FEE[1] = "0";
@@ -0,0 +1,7 @@
+const char NEON_SEED_VERSION[] = "3";
+const char NEON_TOKEN_MINT[] = "89dre8rZjLNft7HoupGiyxu3MNftR577ZYu8bHe2kK7g";
+__attribute__((visibility("hidden"))) const char NEON_TOKEN_MINT_DECIMALS[] = "9";
+const char NEON_COMPUTE_UNITS[] = "1400000";
+const char NEON_HEAP_FRAME[] = "262144";
+const char NEON_ADDITIONAL_FEE[] = "5000";
+const char NEON_PKG_VERSION[] = "\xff\xfe";